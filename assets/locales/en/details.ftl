@@ -6,4 +6,10 @@ details-play = Play
 details-kill = Kill
 details-verify = Verify
 details-manage-addons = Manage addons
+details-wipe-storage = Wipe script data
+details-storage-wiped = {$game-title} script data wiped
 details-download = Download
+details-predownload = Pre-download {$version}
+details-predownloaded = {$version} is pre-downloaded
+details-news = News
+details-game-settings = Game settings
@@ -1,7 +1,9 @@
 dialog-toast-details = Details
+dialog-error-details = Error details
 
-dialog-stop     = Stop
-dialog-disable  = Disable
-dialog-continue = Continue
-dialog-close    = Close
-dialog-save     = Save
+dialog-stop         = Stop
+dialog-disable      = Disable
+dialog-continue     = Continue
+dialog-close        = Close
+dialog-save         = Save
+dialog-copy-details = Copy details
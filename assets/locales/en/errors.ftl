@@ -13,6 +13,7 @@ addon-not-installed              = {$game-title} addon is not installed
 game-verify-installation-failed = Unable to verify {$game-title} installation
 game-find-diff-failed           = Unable to find {$game-title} version diff
 game-find-download-failed       = Unable to find {$game-title} download info
+game-find-predownload-failed    = Unable to find {$game-title} pre-download info
 game-find-path-failed           = Unable to find {$game-title} installation path
 game-get-settings-failed        = Unable to get {$game-title} settings
 game-get-addons-failed          = Unable to get {$game-title} addons
@@ -20,8 +21,10 @@ game-get-paths-failed           = Unable to get {$game-title} paths
 game-get-integrity-failed       = Unable to get {$game-title} integrity info
 game-get-status-failed          = Unable to get {$game-title} status
 game-not-installed              = {$game-title} is not installed
+game-predownload-unavailable    = {$game-title} pre-download is not available
 game-launch-failed              = Failed to launch {$game-title}
 game-kill-failed                = Failed to kill {$game-title}
+game-wipe-storage-failed        = Failed to wipe {$game-title} script data
 
 launch-required-addon-unavailable = Addon {$addon-title} from group {$group-title} is unavailable or outdated. You can launch the game without it or continue to use old version
 
@@ -41,3 +44,8 @@ loading-checking-games-addons-failed = Failed to check games addons
 main-open-launcher-folder-failed = Failed to open launcher folder
 main-open-config-file-failed     = Failed to open config file
 main-open-debug-file-failed      = Failed to open debug file
+main-refresh-library-failed      = Failed to refresh games library
+main-reload-integration-failed   = Failed to reload {$game-name} integration script
+
+integration-script-timed-out = Integration script timed out: {$game-name} didn't finish {$api} in {$seconds} seconds
+integration-script-failed    = Integration script {$game-name} failed to execute {$api}: {$message}
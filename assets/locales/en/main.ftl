@@ -1,6 +1,7 @@
 main-menu-launcher-folder = Launcher folder
 main-menu-config-file     = Config file
 main-menu-debug-file      = Debug file
+main-menu-refresh-library = Refresh library
 main-menu-about           = About
 
 main-installed-games = Installed games
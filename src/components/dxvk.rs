@@ -3,7 +3,6 @@ use std::path::PathBuf;
 
 use serde_json::Value as Json;
 
-use anime_game_core::network::minreq;
 use anime_game_core::archive;
 
 use anime_game_core::network::downloader::DownloaderExt;
@@ -13,6 +12,7 @@ use anime_game_core::updater::UpdaterExt;
 
 use crate::{
     config,
+    http_cache,
     COMPONENTS_FOLDER
 };
 
@@ -39,8 +39,8 @@ impl Dxvk {
     pub fn versions() -> anyhow::Result<Vec<Self>> {
         let components = config::get().components;
 
-        let dxvk_versions = minreq::get(format!("{}/dxvk/{}.json", &components.channel, &components.dxvk.build))
-            .send()?.json::<Vec<Json>>()?;
+        let dxvk_versions = http_cache::get(format!("{}/dxvk/{}.json", &components.channel, &components.dxvk.build))?
+            .json::<Vec<Json>>()?;
 
         let mut versions = Vec::new();
 
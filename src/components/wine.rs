@@ -11,7 +11,6 @@ use wincompatlib::wine::{
     WineLoader as WincompatlibWineLoader
 };
 
-use anime_game_core::network::minreq;
 use anime_game_core::archive;
 
 use anime_game_core::network::downloader::DownloaderExt;
@@ -24,6 +23,7 @@ use crate::ui::components::tasks_queue::{QueuedTask, ResolvedTask};
 
 use crate::{
     config,
+    http_cache,
     COMPONENTS_FOLDER
 };
 
@@ -46,8 +46,8 @@ impl Wine {
     pub fn versions() -> anyhow::Result<Vec<Self>> {
         let components = config::get().components;
 
-        let wine_versions = minreq::get(format!("{}/wine/{}.json", &components.channel, &components.wine.build))
-            .send()?.json::<Vec<Json>>()?;
+        let wine_versions = http_cache::get(format!("{}/wine/{}.json", &components.channel, &components.wine.build))?
+            .json::<Vec<Json>>()?;
 
         let mut versions = Vec::new();
 
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value as Json;
+
+use crate::i18n;
+use crate::LAUNCHER_FOLDER;
+
+use crate::games::integrations::capabilities::Capability;
+
+pub mod timeouts;
+
+use timeouts::Timeouts;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Integrations {
+    pub sources: Vec<String>,
+    pub path: PathBuf,
+
+    /// Folder with lua modules shared between integration scripts
+    pub shared_path: PathBuf,
+
+    /// Capabilities approved for every integration script
+    pub capabilities: Vec<Capability>,
+
+    /// Additional capabilities approved for specific integration scripts
+    pub games_capabilities: HashMap<String, Vec<Capability>>,
+
+    /// Execution time limits of the integration API calls
+    pub timeouts: Timeouts,
+
+    /// Minisign public keys which can sign integration updates
+    pub trusted_keys: Vec<String>,
+
+    /// Accept updates without signatures if there are no trusted keys
+    /// 
+    /// Updates with invalid signatures are always rejected
+    pub allow_unsigned: bool
+}
+
+impl Default for Integrations {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            sources: if i18n::get_system_language() == "zh_cn" {
+                vec![
+                    String::from("https://raw.gitmirror.com/an-anime-team/game-integrations/main")
+                ]
+            } else {
+                vec![
+                    String::from("https://raw.githubusercontent.com/an-anime-team/game-integrations/main")
+                ]
+            },
+
+            path: LAUNCHER_FOLDER.join("integrations"),
+            shared_path: LAUNCHER_FOLDER.join("integrations_shared"),
+
+            capabilities: Capability::default_approved(),
+            games_capabilities: HashMap::new(),
+            timeouts: Timeouts::default(),
+
+            trusted_keys: Vec::new(),
+            allow_unsigned: true
+        }
+    }
+}
+
+impl From<&Json> for Integrations {
+    #[inline]
+    fn from(value: &Json) -> Self {
+        let default = Self::default();
+
+        Self {
+            sources: value.get("sources")
+                .and_then(Json::as_array)
+                .map(|sources| sources.iter()
+                    .filter_map(|source| source.as_str()
+                    .map(String::from))
+                    .collect()
+                )
+                .unwrap_or(default.sources),
+
+            path: value.get("path")
+                .and_then(Json::as_str)
+                .map(PathBuf::from)
+                .unwrap_or(default.path),
+
+            shared_path: value.get("shared_path")
+                .and_then(Json::as_str)
+                .map(PathBuf::from)
+                .unwrap_or(default.shared_path),
+
+            capabilities: value.get("capabilities")
+                .and_then(|capabilities| serde_json::from_value(capabilities.clone()).ok())
+                .unwrap_or(default.capabilities),
+
+            games_capabilities: value.get("games_capabilities")
+                .and_then(|capabilities| serde_json::from_value(capabilities.clone()).ok())
+                .unwrap_or(default.games_capabilities),
+
+            timeouts: value.get("timeouts")
+                .map(Timeouts::from)
+                .unwrap_or(default.timeouts),
+
+            trusted_keys: value.get("trusted_keys")
+                .and_then(Json::as_array)
+                .map(|keys| keys.iter()
+                    .filter_map(|key| key.as_str()
+                    .map(String::from))
+                    .collect()
+                )
+                .unwrap_or(default.trusted_keys),
+
+            allow_unsigned: value.get("allow_unsigned")
+                .and_then(Json::as_bool)
+                .unwrap_or(default.allow_unsigned)
+        }
+    }
+}
+
+impl Integrations {
+    /// Get list of capabilities approved for the given integration script
+    pub fn get_approved_capabilities(&self, game_name: impl AsRef<str>) -> Vec<Capability> {
+        let mut capabilities = self.capabilities.clone();
+
+        if let Some(game_capabilities) = self.games_capabilities.get(game_name.as_ref()) {
+            for capability in game_capabilities {
+                if !capabilities.contains(capability) {
+                    capabilities.push(*capability);
+                }
+            }
+        }
+
+        capabilities
+    }
+}
@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value as Json;
+
+use crate::games::integrations::budget::ApiCategory;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Execution time limits of the integration API calls in seconds
+/// 
+/// `0` disables the limit
+pub struct Timeouts {
+    /// Script loading
+    pub load: u64,
+
+    /// Pictures and styles
+    pub visual: u64,
+
+    /// Game info, status and launch options
+    pub game: u64,
+
+    /// Addons info and status
+    pub addons: u64,
+
+    /// Diff transition hooks
+    pub hooks: u64,
+
+    /// Custom integrity hashes
+    pub integrity: u64
+}
+
+impl Default for Timeouts {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            load: 15,
+            visual: 15,
+            game: 30,
+            addons: 30,
+
+            // Transition hooks can process lots of files
+            hooks: 3600,
+
+            integrity: 60
+        }
+    }
+}
+
+impl From<&Json> for Timeouts {
+    #[inline]
+    fn from(value: &Json) -> Self {
+        let default = Self::default();
+
+        Self {
+            load: value.get("load")
+                .and_then(Json::as_u64)
+                .unwrap_or(default.load),
+
+            visual: value.get("visual")
+                .and_then(Json::as_u64)
+                .unwrap_or(default.visual),
+
+            game: value.get("game")
+                .and_then(Json::as_u64)
+                .unwrap_or(default.game),
+
+            addons: value.get("addons")
+                .and_then(Json::as_u64)
+                .unwrap_or(default.addons),
+
+            hooks: value.get("hooks")
+                .and_then(Json::as_u64)
+                .unwrap_or(default.hooks),
+
+            integrity: value.get("integrity")
+                .and_then(Json::as_u64)
+                .unwrap_or(default.integrity)
+        }
+    }
+}
+
+impl Timeouts {
+    /// Get time limit of the given API category
+    pub fn get(&self, category: ApiCategory) -> Option<Duration> {
+        let timeout = match category {
+            ApiCategory::Load      => self.load,
+            ApiCategory::Visual    => self.visual,
+            ApiCategory::Game      => self.game,
+            ApiCategory::Addons    => self.addons,
+            ApiCategory::Hooks     => self.hooks,
+            ApiCategory::Integrity => self.integrity
+        };
+
+        if timeout == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(timeout))
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use serde::{Serialize, Deserialize};
-use serde_json::Value as Json;
+use serde_json::{Value as Json, Map};
 
 use crate::config;
 use crate::games::integrations::Game;
@@ -97,4 +97,16 @@ impl Games {
 
         Ok(settings)
     }
+
+    /// Get stored values of the integration script options
+    ///
+    /// Unlike `get_game_settings` this method doesn't call the integration script
+    pub fn get_game_values(&self, game_name: impl AsRef<str>, edition: impl AsRef<str>) -> Map<String, Json> {
+        self.settings.get(game_name.as_ref())
+            .and_then(|settings| settings.get("values"))
+            .and_then(|values| values.get(edition.as_ref()))
+            .and_then(Json::as_object)
+            .cloned()
+            .unwrap_or_default()
+    }
 }
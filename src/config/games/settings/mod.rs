@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use serde::{Serialize, Deserialize};
-use serde_json::Value as Json;
+use serde_json::{Value as Json, Map};
 
 pub mod edition_addons;
 pub mod edition_paths;
@@ -17,7 +17,10 @@ use prelude::*;
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GameSettings {
     pub paths: HashMap<String, GameEditionPaths>,
-    pub addons: HashMap<String, Vec<GameEditionAddon>>
+    pub addons: HashMap<String, Vec<GameEditionAddon>>,
+
+    /// Values of the options declared by the integration script
+    pub values: HashMap<String, Map<String, Json>>
 }
 
 impl GameSettings {
@@ -34,9 +37,15 @@ impl GameSettings {
                 }).collect(),
 
             addons: edition_names
+                .clone()
                 .into_iter()
                 .map(|edition| (edition.as_ref().to_string(), vec![]))
                 .collect::<HashMap<_, _>>(),
+
+            values: edition_names
+                .into_iter()
+                .map(|edition| (edition.as_ref().to_string(), Map::new()))
+                .collect::<HashMap<_, _>>()
         })
     }
 
@@ -63,9 +72,18 @@ impl GameSettings {
             }
         }
 
+        if let Some(values) = value.get("values").and_then(Json::as_object) {
+            for (edition, edition_values) in values.clone() {
+                if let Json::Object(edition_values) = edition_values {
+                    default.values.insert(edition, edition_values);
+                }
+            }
+        }
+
         Ok(Self {
             paths: default.paths,
-            addons: default.addons
+            addons: default.addons,
+            values: default.values
         })
     }
 }
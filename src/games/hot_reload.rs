@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+use notify::{Watcher, RecursiveMode};
+
+use crate::config;
+
+use super::integrations::manifest::Manifest;
+
+/// Time to wait for the following file changes
+/// before reloading the integration scripts
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Watch integration scripts folders for changes
+/// 
+/// Callback is called with the name of the game which integration
+/// files were changed, and it should call `reload` for it.
+/// Changes in the shared modules folder affect all the scripts
+pub fn watch(callback: impl Fn(String) + Send + 'static) -> anyhow::Result<()> {
+    let config = config::get().games.integrations;
+
+    let (sender, receiver) = flume::unbounded();
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        match event {
+            Ok(event) => {
+                let _ = sender.send(event.paths);
+            }
+
+            Err(err) => tracing::error!("Failed to watch integration scripts: {err}")
+        }
+    })?;
+
+    watcher.watch(&config.path, RecursiveMode::Recursive)?;
+
+    if config.shared_path.exists() {
+        watcher.watch(&config.shared_path, RecursiveMode::Recursive)?;
+    }
+
+    std::thread::Builder::new()
+        .name(String::from("integrations-hot-reload"))
+        .spawn(move || {
+            // Watcher stops when it's dropped
+            let _watcher = watcher;
+
+            while let Ok(paths) = receiver.recv() {
+                let mut paths = paths;
+
+                // Editors write files in several steps
+                while let Ok(next_paths) = receiver.recv_timeout(DEBOUNCE_TIMEOUT) {
+                    paths.extend(next_paths);
+                }
+
+                let mut changed = HashSet::new();
+
+                for path in paths {
+                    if path.starts_with(&config.shared_path) {
+                        match games_names(&config.path) {
+                            Ok(games) => changed.extend(games),
+                            Err(err) => tracing::error!("Failed to list integrations: {err}")
+                        }
+                    }
+
+                    else if let Some(game) = game_name(&config.path, &path) {
+                        // Scripts can write their own files (e.g. caches)
+                        // which shouldn't cause endless reloads
+                        if is_integration_file(&config.path.join(&game), &path) {
+                            changed.insert(game);
+                        }
+                    }
+                }
+
+                for game_name in changed {
+                    callback(game_name);
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+/// Reload integration script of the given game
+/// 
+/// Only the lua state of the game driver is replaced, so it can be
+/// called from any thread. Games list and UI are not updated
+pub fn reload(game_name: &str) -> anyhow::Result<()> {
+    let Some(game) = super::get(game_name)? else {
+        anyhow::bail!("Integration {game_name} is not loaded");
+    };
+
+    tracing::info!("Reloading {game_name} integration script");
+
+    game.reload(config::get().games.integrations.path.join(game_name).join("manifest.json"))
+}
+
+/// Get names of all the integrations folders
+fn games_names(integrations: &Path) -> anyhow::Result<Vec<String>> {
+    let mut names = Vec::new();
+
+    for entry in std::fs::read_dir(integrations)?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        if entry.path().is_dir() && !name.starts_with('.') {
+            names.push(name);
+        }
+    }
+
+    Ok(names)
+}
+
+/// Check if the path is the manifest or one of the files listed in it
+fn is_integration_file(integration_path: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(integration_path) else {
+        return false;
+    };
+
+    if relative == Path::new("manifest.json") {
+        return true;
+    }
+
+    let manifest = std::fs::read(integration_path.join("manifest.json")).ok()
+        .and_then(|manifest| serde_json::from_slice(&manifest).ok())
+        .and_then(|manifest| Manifest::from_json(&manifest).ok());
+
+    match manifest {
+        Some(manifest) => manifest.files().into_iter()
+            .any(|file| Path::new(file) == relative),
+
+        // Broken manifest is reported by the reloading
+        None => true
+    }
+}
+
+/// Get name of the game which integration folder contains given path
+fn game_name(integrations: &Path, path: &Path) -> Option<String> {
+    let name = path.strip_prefix(integrations).ok()?
+        .components()
+        .next()?
+        .as_os_str()
+        .to_string_lossy()
+        .to_string();
+
+    // Hidden folders (e.g. quarantined updates) don't contain integrations
+    if name.starts_with('.') {
+        return None;
+    }
+
+    Some(name)
+}
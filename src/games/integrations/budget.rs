@@ -0,0 +1,120 @@
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use mlua::prelude::*;
+use mlua::HookTriggers;
+
+use crate::tr;
+
+/// Amount of lua VM instructions executed between budget checks
+const INSTRUCTIONS_PER_CHECK: u32 = 10000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiCategory {
+    /// Top-level code of the script
+    Load,
+
+    Visual,
+    Game,
+    Addons,
+    Hooks,
+    Integrity
+}
+
+impl ApiCategory {
+    /// Get category of the integration API function from its V2 path
+    pub fn from_path(path: &str) -> Self {
+        match path.split('.').next() {
+            Some("visual") => Self::Visual,
+            Some("addons") => Self::Addons,
+
+            Some("hooks") if path == "hooks.integrity_hash" => Self::Integrity,
+            Some("hooks") => Self::Hooks,
+
+            _ => Self::Game
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Error returned when the integration API function exceeded its execution budget
+pub struct TimeoutError {
+    pub game_name: String,
+    pub api: String,
+    pub timeout: Duration
+}
+
+impl std::fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", tr!("integration-script-timed-out", {
+            "game-name" = self.game_name.as_str(),
+            "api" = self.api.as_str(),
+            "seconds" = self.timeout.as_secs()
+        }))
+    }
+}
+
+impl std::error::Error for TimeoutError {}
+
+#[derive(Debug, Clone, Default)]
+/// Wall-clock execution budget of the integration API calls
+pub struct Budget {
+    deadline: Arc<Mutex<Option<Instant>>>,
+    exceeded: Arc<AtomicBool>
+}
+
+impl Budget {
+    /// Install budget checking hook to the lua state
+    pub fn install(lua: &Lua) -> Self {
+        let budget = Self::default();
+        let hook_budget = budget.clone();
+
+        lua.set_hook(HookTriggers::new().every_nth_instruction(INSTRUCTIONS_PER_CHECK), move |_, _| {
+            hook_budget.check()
+        });
+
+        budget
+    }
+
+    /// Get time remaining in the current budget period
+    /// 
+    /// `None` if the budget is disabled
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline.lock()
+            .expect("Failed to lock budget deadline")
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Return an error if the budget was exceeded
+    /// 
+    /// Long running built-ins call it to stop
+    /// without waiting for the lua code to continue
+    pub fn check(&self) -> LuaResult<()> {
+        if self.remaining() == Some(Duration::ZERO) {
+            self.exceeded.store(true, Ordering::Relaxed);
+
+            return Err(LuaError::RuntimeError(String::from("Execution budget exceeded")));
+        }
+
+        Ok(())
+    }
+
+    /// Start new budget period
+    /// 
+    /// `None` disables the budget
+    pub fn start(&self, timeout: Option<Duration>) {
+        *self.deadline.lock().expect("Failed to lock budget deadline") = timeout.map(|timeout| Instant::now() + timeout);
+
+        self.exceeded.store(false, Ordering::Relaxed);
+    }
+
+    /// Finish current budget period
+    /// 
+    /// Return `true` if the budget was exceeded
+    pub fn finish(&self) -> bool {
+        *self.deadline.lock().expect("Failed to lock budget deadline") = None;
+
+        self.exceeded.swap(false, Ordering::Relaxed)
+    }
+}
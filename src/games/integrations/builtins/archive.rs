@@ -0,0 +1,42 @@
+use mlua::prelude::*;
+
+use anime_game_core::archive;
+
+use super::{Builtins, Progress};
+use super::fs::FilesystemRoots;
+
+use crate::games::integrations::budget::Budget;
+
+pub fn register(builtins: &Builtins, roots: &FilesystemRoots, progress: &Progress, budget: &Budget) -> LuaResult<()> {
+    let lua = builtins.lua();
+
+    let roots = roots.clone();
+    let progress = progress.clone();
+    let budget = budget.clone();
+
+    builtins.set("archive.extract", lua.create_function(move |_, (archive, folder): (String, String)| {
+        let archive = roots.resolve(archive)?;
+        let folder = roots.resolve(folder)?;
+
+        let Some(mut updater) = archive::extract(&archive, &folder) else {
+            return Err(LuaError::RuntimeError(format!("Failed to extract files from the archive: {archive:?}")));
+        };
+
+        loop {
+            budget.check()?;
+
+            match updater.status() {
+                Ok(false) => progress.report(updater.current(), updater.total()),
+                Ok(true) => break,
+
+                Err(err) => return Err(LuaError::RuntimeError(format!("Failed to extract files from the archive {archive:?}: {err}")))
+            }
+        }
+
+        progress.report(updater.total(), updater.total());
+
+        Ok(())
+    })?)?;
+
+    Ok(())
+}
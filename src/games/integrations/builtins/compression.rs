@@ -0,0 +1,35 @@
+use std::io::Read;
+
+use mlua::prelude::*;
+
+use super::Builtins;
+
+fn read_all(mut reader: impl Read) -> LuaResult<Vec<u8>> {
+    let mut data = Vec::new();
+
+    reader.read_to_end(&mut data)
+        .map_err(LuaError::external)?;
+
+    Ok(data)
+}
+
+pub fn register(builtins: &Builtins) -> LuaResult<()> {
+    let lua = builtins.lua();
+
+    builtins.set("compression.zstd_decompress", lua.create_function(|lua, data: LuaString| {
+        let decoder = zstd::Decoder::new(data.as_bytes())
+            .map_err(LuaError::external)?;
+
+        lua.create_string(read_all(decoder)?)
+    })?)?;
+
+    builtins.set("compression.gzip_decompress", lua.create_function(|lua, data: LuaString| {
+        lua.create_string(read_all(flate2::read::GzDecoder::new(data.as_bytes()))?)
+    })?)?;
+
+    builtins.set("compression.deflate_decompress", lua.create_function(|lua, data: LuaString| {
+        lua.create_string(read_all(flate2::read::DeflateDecoder::new(data.as_bytes()))?)
+    })?)?;
+
+    Ok(())
+}
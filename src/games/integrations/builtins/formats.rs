@@ -0,0 +1,124 @@
+use serde_json::{Value as Json, Map};
+
+use mlua::prelude::*;
+
+use ini::Ini;
+
+use super::Builtins;
+
+pub fn register(builtins: &Builtins) -> LuaResult<()> {
+    let lua = builtins.lua();
+
+    builtins.set("ini.decode", lua.create_function(|lua, ini: String| {
+        let ini = Ini::load_from_str(&ini).map_err(LuaError::external)?;
+
+        // General section is stored separately so sections
+        // can't overwrite its properties with the same name
+        let mut general = Map::new();
+        let mut sections = Map::new();
+
+        for (section, properties) in ini.iter() {
+            let properties = properties.iter()
+                .map(|(key, value)| (key.to_string(), Json::String(value.to_string())));
+
+            match section {
+                None => general.extend(properties),
+
+                Some(section) => match sections.get_mut(section) {
+                    Some(Json::Object(table)) => table.extend(properties),
+
+                    _ => {
+                        sections.insert(section.to_string(), Json::Object(properties.collect()));
+                    }
+                }
+            }
+        }
+
+        let mut value = Map::new();
+
+        value.insert(String::from("general"), Json::Object(general));
+        value.insert(String::from("sections"), Json::Object(sections));
+
+        lua.to_value(&value)
+    })?)?;
+
+    builtins.set("ini.encode", lua.create_function(|lua, value: LuaValue| {
+        let value = lua.from_value::<Json>(value)?;
+
+        let mut ini = Ini::new();
+
+        // General section must go first, and both sections and
+        // their properties are sorted by the json map
+        for (key, value) in &ini_table(value.get("general"), "general")? {
+            ini.with_general_section().set(key, ini_value(value)?);
+        }
+
+        for (section, properties) in &ini_table(value.get("sections"), "sections")? {
+            let properties = ini_table(Some(properties), section)?;
+
+            let section = ini.entry(Some(section.clone()))
+                .or_insert_with(Default::default);
+
+            for (key, value) in &properties {
+                section.insert(key, ini_value(value)?);
+            }
+        }
+
+        let mut result = Vec::new();
+
+        ini.write_to(&mut result).map_err(LuaError::external)?;
+
+        String::from_utf8(result).map_err(LuaError::external)
+    })?)?;
+
+    builtins.set("toml.decode", lua.create_function(|lua, toml: String| {
+        toml::from_str::<Json>(&toml)
+            .map(|value| lua.to_value(&value))
+            .map_err(LuaError::external)
+    })?)?;
+
+    builtins.set("toml.encode", lua.create_function(|lua, value: LuaValue| {
+        let value = lua.from_value::<Json>(value)?;
+
+        toml::to_string(&value).map_err(LuaError::external)
+    })?)?;
+
+    builtins.set("yaml.decode", lua.create_function(|lua, yaml: String| {
+        serde_yaml::from_str::<Json>(&yaml)
+            .map(|value| lua.to_value(&value))
+            .map_err(LuaError::external)
+    })?)?;
+
+    builtins.set("yaml.encode", lua.create_function(|lua, value: LuaValue| {
+        let value = lua.from_value::<Json>(value)?;
+
+        serde_yaml::to_string(&value).map_err(LuaError::external)
+    })?)?;
+
+    Ok(())
+}
+
+/// Get properties table of the INI value
+fn ini_table(value: Option<&Json>, name: &str) -> LuaResult<Map<String, Json>> {
+    match value {
+        None | Some(Json::Null) => Ok(Map::new()),
+
+        // Empty lua tables are converted to arrays
+        Some(Json::Array(array)) if array.is_empty() => Ok(Map::new()),
+
+        Some(Json::Object(table)) => Ok(table.clone()),
+
+        Some(_) => Err(LuaError::external(format!("INI field `{name}` must be a table")))
+    }
+}
+
+/// Convert json value to the INI property value
+fn ini_value(value: &Json) -> LuaResult<String> {
+    match value {
+        Json::String(value) => Ok(value.clone()),
+        Json::Number(value) => Ok(value.to_string()),
+        Json::Bool(value) => Ok(value.to_string()),
+
+        _ => Err(LuaError::external("INI property value must be a string, number or boolean"))
+    }
+}
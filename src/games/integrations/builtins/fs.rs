@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf, Component};
+use std::sync::{Arc, RwLock};
+
+use mlua::prelude::*;
+
+use crate::games::integrations::capabilities::{
+    Capability,
+    FilesystemScope
+};
+
+use super::Builtins;
+
+#[derive(Debug, Clone)]
+/// Folders accessible by the integration script
+///
+/// Game, addons and transition folders are set by the driver
+/// for the duration of the API call which received them
+pub struct FilesystemRoots {
+    granted: Vec<FilesystemScope>,
+    roots: Arc<RwLock<HashMap<FilesystemScope, PathBuf>>>
+}
+
+impl FilesystemRoots {
+    pub fn new(capabilities: &[Capability]) -> Self {
+        Self {
+            granted: capabilities.iter()
+                .filter_map(|capability| match capability {
+                    Capability::Filesystem(scope) => Some(*scope),
+                    _ => None
+                })
+                .collect(),
+
+            roots: Arc::new(RwLock::new(HashMap::new()))
+        }
+    }
+
+    #[inline]
+    /// Check if the script has access to any filesystem scope
+    pub fn has_access(&self) -> bool {
+        !self.granted.is_empty()
+    }
+
+    /// Make given folder accessible by the script if its scope was granted
+    pub fn set(&self, scope: FilesystemScope, path: impl Into<PathBuf>) {
+        if self.granted.contains(&scope) {
+            let path = path.into();
+
+            let path = std::fs::canonicalize(&path)
+                .unwrap_or_else(|_| normalize(&path).unwrap_or(path));
+
+            self.roots.write()
+                .expect("Failed to lock filesystem roots")
+                .insert(scope, path);
+        }
+    }
+
+    /// Remove folder of the given scope from the accessible roots
+    pub fn remove(&self, scope: FilesystemScope) {
+        self.roots.write()
+            .expect("Failed to lock filesystem roots")
+            .remove(&scope);
+    }
+
+    /// Resolve given path and verify that it's located inside of the accessible roots
+    pub fn resolve(&self, path: impl AsRef<Path>) -> LuaResult<PathBuf> {
+        let path = path.as_ref();
+
+        if !path.is_absolute() {
+            return Err(LuaError::RuntimeError(format!("Path must be absolute: {path:?}")));
+        }
+
+        let Some(normalized) = normalize(path) else {
+            return Err(LuaError::RuntimeError(format!("Path escapes the filesystem root: {path:?}")));
+        };
+
+        if self.granted.contains(&FilesystemScope::System) {
+            return Ok(normalized);
+        }
+
+        // Resolve symlinks in the existing part of the path
+        // so they can't be used to escape allowed folders
+        let mut existing = normalized.as_path();
+        let mut rest = Vec::new();
+
+        let resolved = loop {
+            if let Ok(path) = std::fs::canonicalize(existing) {
+                break rest.into_iter().rev().fold(path, |path: PathBuf, name| path.join(name));
+            }
+
+            match (existing.parent(), existing.file_name()) {
+                (Some(parent), Some(name)) => {
+                    rest.push(name.to_owned());
+
+                    existing = parent;
+                }
+
+                _ => break normalized.clone()
+            }
+        };
+
+        let roots = self.roots.read()
+            .expect("Failed to lock filesystem roots");
+
+        if roots.values().any(|root| resolved.starts_with(root)) {
+            return Ok(resolved);
+        }
+
+        Err(LuaError::RuntimeError(format!("Path is outside of the allowed folders: {path:?}")))
+    }
+
+    /// Resolve given path without following a symlink in its last component
+    ///
+    /// Used by the functions which work with the filesystem entry
+    /// itself rather than with the file it points to
+    pub fn resolve_entry(&self, path: impl AsRef<Path>) -> LuaResult<PathBuf> {
+        let path = path.as_ref();
+
+        if !path.is_absolute() {
+            return Err(LuaError::RuntimeError(format!("Path must be absolute: {path:?}")));
+        }
+
+        let Some(normalized) = normalize(path) else {
+            return Err(LuaError::RuntimeError(format!("Path escapes the filesystem root: {path:?}")));
+        };
+
+        match (normalized.parent(), normalized.file_name()) {
+            (Some(parent), Some(name)) => match self.resolve(parent) {
+                Ok(parent) => Ok(parent.join(name)),
+
+                // Parent folder of the root itself is not accessible
+                Err(_) => self.resolve(&normalized)
+            }
+
+            _ => self.resolve(&normalized)
+        }
+    }
+}
+
+/// Lexically normalize absolute path, resolving `.` and `..` components
+///
+/// Return `None` if the path tries to go above the filesystem root
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => normalized.push(component),
+            Component::CurDir => (),
+
+            Component::ParentDir => {
+                if !normalized.pop() || normalized.as_os_str().is_empty() {
+                    return None;
+                }
+            }
+
+            Component::Normal(name) => normalized.push(name)
+        }
+    }
+
+    Some(normalized)
+}
+
+/// Copy file or folder without following symlinks
+///
+/// Every nested entry is resolved against the accessible roots
+/// before it's read or written. Symlinks are recreated as symlinks
+fn copy_recursive(roots: &FilesystemRoots, from: &Path, to: &Path) -> LuaResult<()> {
+    let from = roots.resolve_entry(from)?;
+    let to = roots.resolve(to)?;
+
+    let metadata = std::fs::symlink_metadata(&from)
+        .map_err(LuaError::external)?;
+
+    if metadata.is_dir() {
+        std::fs::create_dir_all(&to).map_err(LuaError::external)?;
+
+        for entry in from.read_dir().map_err(LuaError::external)? {
+            let entry = entry.map_err(LuaError::external)?;
+
+            copy_recursive(roots, &entry.path(), &to.join(entry.file_name()))?;
+        }
+
+        return Ok(());
+    }
+
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent).map_err(LuaError::external)?;
+    }
+
+    if metadata.is_symlink() {
+        let target = std::fs::read_link(&from)
+            .map_err(LuaError::external)?;
+
+        std::os::unix::fs::symlink(target, &to)
+            .map_err(LuaError::external)?;
+    }
+
+    else {
+        std::fs::copy(&from, &to).map_err(LuaError::external)?;
+    }
+
+    Ok(())
+}
+
+fn entry_type(file_type: std::fs::FileType) -> &'static str {
+    if file_type.is_symlink() {
+        "symlink"
+    } else if file_type.is_dir() {
+        "directory"
+    } else {
+        "file"
+    }
+}
+
+pub fn register(builtins: &Builtins, roots: &FilesystemRoots) -> LuaResult<()> {
+    let lua = builtins.lua();
+
+    let fs_roots = roots.clone();
+
+    builtins.set("fs.exists", lua.create_function(move |_, path: String| {
+        Ok(fs_roots.resolve(path)?.exists())
+    })?)?;
+
+    let fs_roots = roots.clone();
+
+    builtins.set("fs.metadata", lua.create_function(move |lua, path: String| {
+        let metadata = std::fs::symlink_metadata(fs_roots.resolve_entry(path)?)
+            .map_err(LuaError::external)?;
+
+        let result = lua.create_table()?;
+
+        result.set("type", entry_type(metadata.file_type()))?;
+        result.set("size", metadata.len())?;
+
+        if let Ok(modified) = metadata.modified() {
+            if let Ok(modified) = modified.duration_since(std::time::UNIX_EPOCH) {
+                result.set("modified", modified.as_secs())?;
+            }
+        }
+
+        Ok(result)
+    })?)?;
+
+    let fs_roots = roots.clone();
+
+    builtins.set("fs.list", lua.create_function(move |lua, path: String| {
+        let entries = lua.create_table()?;
+
+        for entry in fs_roots.resolve(path)?.read_dir().map_err(LuaError::external)?.flatten() {
+            // Skip entries which were removed or can't be accessed
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            let table = lua.create_table()?;
+
+            table.set("name", entry.file_name().to_string_lossy())?;
+            table.set("path", entry.path().to_string_lossy())?;
+            table.set("type", entry_type(file_type))?;
+
+            entries.push(table)?;
+        }
+
+        Ok(entries)
+    })?)?;
+
+    let fs_roots = roots.clone();
+
+    builtins.set("fs.read", lua.create_function(move |lua, path: String| {
+        let data = std::fs::read(fs_roots.resolve(path)?)
+            .map_err(LuaError::external)?;
+
+        lua.create_string(data)
+    })?)?;
+
+    let fs_roots = roots.clone();
+
+    builtins.set("fs.write", lua.create_function(move |_, (path, data): (String, LuaString)| {
+        let path = fs_roots.resolve(path)?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(LuaError::external)?;
+        }
+
+        std::fs::write(path, data.as_bytes())
+            .map_err(LuaError::external)
+    })?)?;
+
+    let fs_roots = roots.clone();
+
+    builtins.set("fs.create_dir", lua.create_function(move |_, path: String| {
+        std::fs::create_dir_all(fs_roots.resolve(path)?)
+            .map_err(LuaError::external)
+    })?)?;
+
+    let fs_roots = roots.clone();
+
+    builtins.set("fs.copy", lua.create_function(move |_, (from, to): (String, String)| {
+        copy_recursive(&fs_roots, Path::new(&from), Path::new(&to))
+    })?)?;
+
+    let fs_roots = roots.clone();
+
+    builtins.set("fs.move", lua.create_function(move |_, (from, to): (String, String)| {
+        let from = fs_roots.resolve_entry(from)?;
+        let to = fs_roots.resolve(to)?;
+
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent).map_err(LuaError::external)?;
+        }
+
+        // Rename doesn't work between different filesystems
+        if std::fs::rename(&from, &to).is_err() {
+            copy_recursive(&fs_roots, &from, &to)?;
+
+            let metadata = std::fs::symlink_metadata(&from)
+                .map_err(LuaError::external)?;
+
+            if metadata.is_dir() {
+                std::fs::remove_dir_all(&from).map_err(LuaError::external)?;
+            } else {
+                std::fs::remove_file(&from).map_err(LuaError::external)?;
+            }
+        }
+
+        Ok(())
+    })?)?;
+
+    let fs_roots = roots.clone();
+
+    builtins.set("fs.remove", lua.create_function(move |_, path: String| {
+        let path = fs_roots.resolve_entry(path)?;
+
+        let metadata = std::fs::symlink_metadata(&path)
+            .map_err(LuaError::external)?;
+
+        if metadata.is_dir() {
+            std::fs::remove_dir_all(path)
+        } else {
+            std::fs::remove_file(path)
+        }.map_err(LuaError::external)
+    })?)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_folder(name: &str) -> PathBuf {
+        let path = std::env::temp_dir()
+            .join(format!("fs-builtins-test-{}-{name}", std::process::id()));
+
+        let _ = std::fs::remove_dir_all(&path);
+
+        std::fs::create_dir_all(&path).unwrap();
+
+        path.canonicalize().unwrap()
+    }
+
+    fn game_roots(path: &Path) -> FilesystemRoots {
+        let roots = FilesystemRoots::new(&[Capability::Filesystem(FilesystemScope::Game)]);
+
+        roots.set(FilesystemScope::Game, path);
+
+        roots
+    }
+
+    #[test]
+    fn normalize_paths() {
+        assert_eq!(normalize(Path::new("/a/b/../c")), Some(PathBuf::from("/a/c")));
+        assert_eq!(normalize(Path::new("/a/./b/")), Some(PathBuf::from("/a/b")));
+        assert_eq!(normalize(Path::new("/a/..")), Some(PathBuf::from("/")));
+
+        assert_eq!(normalize(Path::new("/..")), None);
+        assert_eq!(normalize(Path::new("/a/../../b")), None);
+    }
+
+    #[test]
+    fn resolve_relative_paths() {
+        let root = temp_folder("relative");
+        let roots = game_roots(&root);
+
+        assert!(roots.resolve("file").is_err());
+        assert!(roots.resolve("./file").is_err());
+        assert!(roots.resolve_entry("../file").is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn resolve_inside_root() {
+        let root = temp_folder("inside");
+        let roots = game_roots(&root);
+
+        assert_eq!(roots.resolve(root.join("file")).unwrap(), root.join("file"));
+        assert_eq!(roots.resolve(root.join("a/../b/file")).unwrap(), root.join("b/file"));
+        assert_eq!(roots.resolve(&root).unwrap(), root);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn resolve_escaping_paths() {
+        let root = temp_folder("escape");
+        let roots = game_roots(&root);
+
+        assert!(roots.resolve(root.join("../file")).is_err());
+        assert!(roots.resolve(root.join("a/../../file")).is_err());
+        assert!(roots.resolve("/etc/passwd").is_err());
+        assert!(roots.resolve("/../../etc/passwd").is_err());
+
+        // Roots are not accessible without granted scope
+        let roots = FilesystemRoots::new(&[]);
+
+        roots.set(FilesystemScope::Game, &root);
+
+        assert!(roots.resolve(root.join("file")).is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn resolve_symlinked_root() {
+        let folder = temp_folder("symlinked-root");
+
+        std::fs::create_dir(folder.join("game")).unwrap();
+        std::os::unix::fs::symlink(folder.join("game"), folder.join("link")).unwrap();
+
+        let roots = game_roots(&folder.join("link"));
+
+        // Paths are resolved to the real folder
+        assert_eq!(roots.resolve(folder.join("link/file")).unwrap(), folder.join("game/file"));
+        assert_eq!(roots.resolve(folder.join("game/file")).unwrap(), folder.join("game/file"));
+
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn resolve_escaping_symlinks() {
+        let folder = temp_folder("escaping-symlinks");
+
+        std::fs::create_dir_all(folder.join("game")).unwrap();
+        std::fs::create_dir_all(folder.join("outside")).unwrap();
+
+        std::os::unix::fs::symlink(folder.join("outside"), folder.join("game/link")).unwrap();
+
+        let roots = game_roots(&folder.join("game"));
+
+        assert!(roots.resolve(folder.join("game/link")).is_err());
+        assert!(roots.resolve(folder.join("game/link/file")).is_err());
+
+        // The link itself is located inside of the root
+        assert_eq!(roots.resolve_entry(folder.join("game/link")).unwrap(), folder.join("game/link"));
+        assert!(roots.resolve_entry(folder.join("game/link/file")).is_err());
+
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+}
@@ -0,0 +1,35 @@
+use mlua::prelude::*;
+
+use super::Builtins;
+use super::fs::FilesystemRoots;
+
+use crate::games::integrations::standards::prelude::*;
+
+pub fn register(builtins: &Builtins, roots: &FilesystemRoots) -> LuaResult<()> {
+    let lua = builtins.lua();
+    let standard = builtins.standard();
+
+    let roots = roots.clone();
+
+    builtins.set("hash", lua.create_function(move |_, (algorithm, data): (String, LuaValue)| {
+        let hash_type = HashType::from_str(&algorithm, standard)
+            .map_err(LuaError::external)?;
+
+        let hash = match data {
+            LuaValue::String(data) => hash_type.hash(data.as_bytes()),
+
+            // Stream files content instead of loading it to lua strings
+            LuaValue::Table(table) => {
+                let path = roots.resolve(table.get::<_, String>("path")?)?;
+
+                hash_type.hash_file(path).map_err(LuaError::external)?
+            }
+
+            _ => return Err(LuaError::RuntimeError(String::from("Hash data must be a string or a table with the file path")))
+        };
+
+        hash.ok_or_else(|| LuaError::RuntimeError(format!("Unsupported hash algorithm: {algorithm}")))
+    })?)?;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use serde_json::Value as Json;
+
+use mlua::prelude::*;
+
+use super::Builtins;
+
+pub fn register(builtins: &Builtins) -> LuaResult<()> {
+    let lua = builtins.lua();
+
+    builtins.set("json.decode", lua.create_function(|lua, json: String| {
+        serde_json::from_str::<Json>(&json)
+            .map(|value| lua.to_value(&value))
+            .map_err(LuaError::external)
+    })?)?;
+
+    builtins.set("json.encode", lua.create_function(|lua, (value, options): (LuaValue, Option<LuaTable>)| {
+        let pretty = match options {
+            Some(options) => options.get::<_, Option<bool>>("pretty")?.unwrap_or_default(),
+            None => false
+        };
+
+        // Objects keys are sorted so the output doesn't change between calls
+        let value = lua.from_value::<Json>(value)?;
+
+        let json = if pretty {
+            serde_json::to_string_pretty(&value)
+        } else {
+            serde_json::to_string(&value)
+        };
+
+        json.map_err(LuaError::external)
+    })?)?;
+
+    Ok(())
+}
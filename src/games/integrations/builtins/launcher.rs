@@ -0,0 +1,43 @@
+use mlua::prelude::*;
+
+use crate::{config, i18n};
+use crate::games::integrations::standards::IntegrationStandard;
+
+use super::Builtins;
+
+pub fn register(builtins: &Builtins) -> LuaResult<()> {
+    let lua = builtins.lua();
+
+    // `v1_launcher_info` for V1 and `launcher.info` for V2 standard
+    let path = match builtins.standard() {
+        IntegrationStandard::V1 => "launcher.info",
+        IntegrationStandard::V2 => "info"
+    };
+
+    // New table is returned on every call so scripts can't change
+    // values seen by the other calls
+    builtins.set(path, lua.create_function(|lua, _: ()| {
+        let config = config::get();
+
+        let info = lua.create_table()?;
+        let wine = lua.create_table()?;
+        let dxvk = lua.create_table()?;
+
+        info.set("version", crate::APP_VERSION)?;
+        info.set("language", i18n::format_language(unsafe { i18n::get_lang() }))?;
+
+        wine.set("build", config.components.wine.build)?;
+        wine.set("version", config.components.wine.version)?;
+        wine.set("prefix", config.components.wine.prefix.path.to_string_lossy())?;
+
+        dxvk.set("build", config.components.dxvk.build)?;
+        dxvk.set("version", config.components.dxvk.version)?;
+
+        info.set("wine", wine)?;
+        info.set("dxvk", dxvk)?;
+
+        Ok(info)
+    })?)?;
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::io::Write;
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use mlua::prelude::*;
+
+use super::Builtins;
+
+use crate::DEBUG_FILE;
+
+/// Get path to the integration script's log file
+pub fn log_file_path(game_name: &str) -> std::path::PathBuf {
+    let name = game_name.replace(['/', '\\'], "_");
+
+    DEBUG_FILE.with_file_name(format!("debug-{name}.log"))
+}
+
+/// Size of the log file after which it's moved to `.old`
+const MAX_LOG_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Open log file keeping previous records since the runtime
+/// is re-created on every integration reload
+fn open_log_file(path: &Path) -> std::io::Result<File> {
+    if path.metadata().map(|metadata| metadata.len() > MAX_LOG_SIZE).unwrap_or_default() {
+        std::fs::rename(path, path.with_extension("log.old"))?;
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+}
+
+pub fn register(builtins: &Builtins) -> LuaResult<()> {
+    let lua = builtins.lua();
+    let game_name = builtins.game_name().to_string();
+
+    let log_file = match open_log_file(&log_file_path(&game_name)) {
+        Ok(file) => Some(Arc::new(Mutex::new(file))),
+        Err(err) => {
+            tracing::warn!("Failed to open {game_name} integration log file: {err}");
+
+            None
+        }
+    };
+
+    let span = tracing::info_span!("integration", game = %game_name);
+
+    for level in ["debug", "info", "warn", "error"] {
+        let log_file = log_file.clone();
+        let span = span.clone();
+
+        builtins.set(&format!("log.{level}"), lua.create_function(move |lua, values: LuaMultiValue| {
+            let tostring = lua.globals().get::<_, LuaFunction>("tostring")?;
+
+            let message = values.into_iter()
+                .map(|value| tostring.call::<_, String>(value))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" ");
+
+            {
+                let _span = span.enter();
+
+                match level {
+                    "debug" => tracing::debug!("{message}"),
+                    "info"  => tracing::info!("{message}"),
+                    "warn"  => tracing::warn!("{message}"),
+                    _       => tracing::error!("{message}")
+                }
+            }
+
+            if let Some(file) = &log_file {
+                let mut file = file.lock().expect("Failed to lock integration log file");
+
+                let time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+
+                writeln!(file, "{time} [{}] {message}", level.to_uppercase())
+                    .map_err(LuaError::external)?;
+            }
+
+            Ok(())
+        })?)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use std::sync::{Arc, Mutex};
+
+use mlua::prelude::*;
+
+use super::standards::IntegrationStandard;
+use super::capabilities::Capability;
+use super::budget::Budget;
+
+pub mod network;
+pub mod json;
+pub mod formats;
+pub mod fs;
+pub mod hash;
+pub mod archive;
+pub mod compression;
+pub mod log;
+pub mod storage;
+pub mod launcher;
+pub mod process;
+
+use fs::FilesystemRoots;
+
+type ProgressCallback = Box<dyn Fn(u64, u64) + Send>;
+
+#[derive(Default, Clone)]
+/// Reporter of the long running built-ins progress to the launcher task
+pub struct Progress(Arc<Mutex<Option<ProgressCallback>>>);
+
+impl Progress {
+    /// Set (or remove) progress callback
+    pub fn set(&self, callback: Option<ProgressCallback>) {
+        *self.0.lock().expect("Failed to lock progress callback") = callback;
+    }
+
+    /// Report progress to the callback if it was set
+    pub fn report(&self, current: u64, total: u64) {
+        if let Some(callback) = self.0.lock().expect("Failed to lock progress callback").as_ref() {
+            callback(current, total);
+        }
+    }
+}
+
+impl std::fmt::Debug for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Progress").finish()
+    }
+}
+
+/// Helper to register built-in functions according to the integration standard
+pub struct Builtins<'lua> {
+    lua: &'lua Lua,
+    standard: IntegrationStandard,
+    game_name: String,
+    launcher: LuaTable<'lua>,
+    features: LuaTable<'lua>
+}
+
+impl<'lua> Builtins<'lua> {
+    pub fn new(lua: &'lua Lua, standard: IntegrationStandard, game_name: impl ToString) -> LuaResult<Self> {
+        Ok(Self {
+            lua,
+            standard,
+            game_name: game_name.to_string(),
+            launcher: lua.create_table()?,
+            features: lua.create_table()?
+        })
+    }
+
+    #[inline]
+    pub fn lua(&self) -> &'lua Lua {
+        self.lua
+    }
+
+    #[inline]
+    pub fn standard(&self) -> IntegrationStandard {
+        self.standard
+    }
+
+    #[inline]
+    pub fn game_name(&self) -> &str {
+        &self.game_name
+    }
+
+    /// Register built-in function by its dot-separated path
+    /// 
+    /// V1 standard stores it as a global variable (`network.fetch` -> `v1_network_fetch`),
+    /// V2 standard stores it in the `launcher` table (`network.fetch` -> `launcher.network.fetch`)
+    pub fn set(&self, path: &str, function: LuaFunction<'lua>) -> LuaResult<()> {
+        match self.standard {
+            IntegrationStandard::V1 => {
+                self.lua.globals().set(format!("v1_{}", path.replace('.', "_")), function)?;
+            }
+
+            IntegrationStandard::V2 => {
+                let mut table = self.launcher.clone();
+                let mut names = path.split('.').peekable();
+
+                while let Some(name) = names.next() {
+                    if names.peek().is_none() {
+                        table.set(name, function)?;
+
+                        break;
+                    }
+
+                    table = match table.get::<_, Option<LuaTable>>(name)? {
+                        Some(table) => table,
+                        None => {
+                            let nested = self.lua.create_table()?;
+
+                            table.set(name, nested.clone())?;
+
+                            nested
+                        }
+                    };
+                }
+
+                self.features.set(path, true)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finish registration
+    /// 
+    /// V2 standard exposes versioned `launcher` table as a global variable
+    pub fn finish(self) -> LuaResult<()> {
+        if self.standard == IntegrationStandard::V2 {
+            self.launcher.set("version", 2)?;
+            self.launcher.set("app_version", crate::APP_VERSION)?;
+            self.launcher.set("features", self.features)?;
+
+            self.lua.globals().set("launcher", self.launcher)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Register built-in APIs allowed by the given capabilities
+pub fn register(
+    lua: &Lua,
+    game_name: &str,
+    standard: IntegrationStandard,
+    capabilities: &[Capability],
+    roots: &FilesystemRoots,
+    progress: &Progress,
+    budget: &Budget
+) -> LuaResult<()> {
+    let builtins = Builtins::new(lua, standard, game_name)?;
+
+    if capabilities.contains(&Capability::Network) {
+        network::register(&builtins, roots, progress, budget)?;
+    }
+
+    if roots.has_access() {
+        fs::register(&builtins, roots)?;
+        archive::register(&builtins, roots, progress, budget)?;
+    }
+
+    log::register(&builtins)?;
+    json::register(&builtins)?;
+    formats::register(&builtins)?;
+    hash::register(&builtins, roots)?;
+    compression::register(&builtins)?;
+    storage::register(&builtins)?;
+    launcher::register(&builtins)?;
+    process::register(&builtins, capabilities.contains(&Capability::Process))?;
+
+    builtins.finish()
+}
@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use serde_json::Value as Json;
+
+use mlua::prelude::*;
+
+use anime_game_core::network::downloader::DownloaderExt;
+use anime_game_core::network::downloader::basic::Downloader;
+
+use super::{Builtins, Progress};
+use super::fs::FilesystemRoots;
+
+use crate::http_cache::{self, Request, Response};
+
+use crate::games::integrations::standards::prelude::*;
+use crate::games::integrations::budget::Budget;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkMode {
+    /// Perform real network requests
+    Live,
+
+    /// Return responses recorded in the fixtures folder
+    Replay(PathBuf),
+
+    /// Perform real network requests and record their responses to the fixtures folder
+    Record(PathBuf)
+}
+
+static NETWORK_MODE: RwLock<NetworkMode> = RwLock::new(NetworkMode::Live);
+
+/// Set network mode of all the integration scripts
+pub fn set_mode(mode: NetworkMode) {
+    *NETWORK_MODE.write().expect("Failed to lock network mode") = mode;
+}
+
+/// Get name of the fixture files of the given request
+fn fixture_name(method: &RequestMethod, uri: &str) -> String {
+    format!("{:x}", xxhash_rust::xxh3::xxh3_64(format!("{method:?} {uri}").as_bytes()))
+}
+
+fn load_fixture(fixtures: &Path, method: &RequestMethod, uri: &str) -> anyhow::Result<Response> {
+    let name = fixture_name(method, uri);
+
+    let Ok(metadata) = std::fs::read(fixtures.join(format!("{name}.json"))) else {
+        anyhow::bail!("No recorded fixture for {method:?} {uri}");
+    };
+
+    let mut response = serde_json::from_slice::<Response>(&metadata)?;
+
+    response.body = std::fs::read(fixtures.join(format!("{name}.body")))?;
+
+    Ok(response)
+}
+
+fn save_fixture(response: &Response, fixtures: &Path, method: &RequestMethod, uri: &str) -> anyhow::Result<()> {
+    let name = fixture_name(method, uri);
+
+    std::fs::create_dir_all(fixtures)?;
+
+    std::fs::write(fixtures.join(format!("{name}.json")), serde_json::to_vec_pretty(response)?)?;
+    std::fs::write(fixtures.join(format!("{name}.body")), &response.body)?;
+
+    Ok(())
+}
+
+/// Get path to the recorded file of the given download
+fn download_fixture(fixtures: &Path, uri: &str) -> PathBuf {
+    fixtures.join(format!("{}.download", fixture_name(&RequestMethod::Get, uri)))
+}
+
+fn response_table(lua: &Lua, response: Response) -> LuaResult<LuaTable> {
+    let result = lua.create_table()?;
+    let headers = lua.create_table()?;
+
+    for (key, value) in &response.headers {
+        headers.set(key.as_str(), value.as_str())?;
+    }
+
+    result.set("url", response.url.as_str())?;
+    result.set("status", response.status)?;
+    result.set("statusText", response.status_text.as_str())?;
+    result.set("ok", response.is_ok())?;
+    result.set("headers", headers)?;
+    result.set("body", lua.create_string(&response.body)?)?;
+
+    result.set("json", lua.create_function(move |lua, _: ()| {
+        response.json::<Json>()
+            .map(|value| lua.to_value(&value))
+            .map_err(LuaError::external)
+    })?)?;
+
+    Ok(result)
+}
+
+/// Limit request timeout by the remaining execution budget
+fn budget_timeout(timeout: Option<u64>, budget: &Budget) -> Option<u64> {
+    match budget.remaining() {
+        Some(remaining) => {
+            let remaining = remaining.as_secs().max(1);
+
+            Some(timeout.map_or(remaining, |timeout| timeout.min(remaining)))
+        }
+
+        None => timeout
+    }
+}
+
+fn fetch(uri: &str, options: RequestOptions, budget: &Budget) -> anyhow::Result<Response> {
+    http_cache::fetch(uri, Request {
+        method: options.method,
+        headers: options.headers.unwrap_or_default(),
+        body: options.body,
+        timeout: budget_timeout(options.timeout, budget),
+        refresh: options.refresh,
+        allow_stale: options.allow_stale
+    })
+}
+
+pub fn register(builtins: &Builtins, roots: &FilesystemRoots, progress: &Progress, budget: &Budget) -> LuaResult<()> {
+    let lua = builtins.lua();
+
+    let fetch_budget = budget.clone();
+
+    builtins.set("network.fetch", lua.create_function(move |lua, (uri, options): (String, Option<RequestOptions>)| {
+        fetch_budget.check()?;
+
+        let options = options.unwrap_or(RequestOptions {
+            method: None,
+            headers: None,
+            body: None,
+            timeout: None,
+            refresh: false,
+            allow_stale: false
+        });
+
+        let method = options.method.clone().unwrap_or(RequestMethod::Get);
+
+        let mode = NETWORK_MODE.read()
+            .expect("Failed to lock network mode")
+            .clone();
+
+        let response = match mode {
+            NetworkMode::Live => fetch(&uri, options, &fetch_budget),
+
+            NetworkMode::Replay(fixtures) => load_fixture(&fixtures, &method, &uri),
+
+            NetworkMode::Record(fixtures) => fetch(&uri, options, &fetch_budget)
+                .and_then(|response| {
+                    save_fixture(&response, &fixtures, &method, &uri)?;
+
+                    Ok(response)
+                })
+        };
+
+        // Report the budget error if the request timed out because of it
+        let response = match response {
+            Ok(response) => response,
+            Err(err) => {
+                fetch_budget.check()?;
+
+                return Err(LuaError::external(err));
+            }
+        };
+
+        response_table(lua, response)
+    })?)?;
+
+    let roots = roots.clone();
+    let progress = progress.clone();
+    let budget = budget.clone();
+
+    builtins.set("network.download", lua.create_function(move |_, (uri, path, options): (String, String, Option<LuaTable>)| {
+        let path = roots.resolve(path)?;
+
+        let resume = match options {
+            Some(options) => options.get::<_, Option<bool>>("resume")?.unwrap_or(true),
+            None => true
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(LuaError::external)?;
+        }
+
+        let mode = NETWORK_MODE.read()
+            .expect("Failed to lock network mode")
+            .clone();
+
+        if let NetworkMode::Replay(fixtures) = &mode {
+            let fixture = download_fixture(fixtures, &uri);
+
+            if !fixture.exists() {
+                return Err(LuaError::RuntimeError(format!("No recorded fixture for download {uri}")));
+            }
+
+            std::fs::copy(fixture, &path).map_err(LuaError::external)?;
+
+            return Ok(());
+        }
+
+        let mut updater = Downloader::new(&uri)
+            .continue_downloading(resume)
+            .download(&path)
+            .map_err(LuaError::external)?;
+
+        while let Ok(false) = updater.status() {
+            budget.check()?;
+
+            progress.report(updater.current(), updater.total());
+        }
+
+        let total = updater.total();
+
+        updater.wait().map_err(LuaError::external)?;
+
+        progress.report(total, total);
+
+        if let NetworkMode::Record(fixtures) = &mode {
+            std::fs::create_dir_all(fixtures).map_err(LuaError::external)?;
+            std::fs::copy(&path, download_fixture(fixtures, &uri)).map_err(LuaError::external)?;
+        }
+
+        Ok(())
+    })?)?;
+
+    Ok(())
+}
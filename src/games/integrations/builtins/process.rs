@@ -0,0 +1,404 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use mlua::prelude::*;
+
+use crate::config;
+
+use super::Builtins;
+
+lazy_static::lazy_static! {
+    /// Root processes of the games started by the launcher
+    static ref LAUNCHED: std::sync::RwLock<HashMap<(String, String), LaunchedProcess>> = Default::default();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LaunchedProcess {
+    /// Process id of the launched command
+    /// 
+    /// Command is started in its own process group,
+    /// so it's also the id of this group
+    pid: u32,
+
+    /// Process start time, used to detect reused pids
+    started_at: u64
+}
+
+#[must_use]
+/// Forget the process started by the launcher when dropped
+pub struct LaunchedGuard {
+    game: String,
+    edition: String
+}
+
+impl Drop for LaunchedGuard {
+    #[inline]
+    fn drop(&mut self) {
+        set_launched(&self.game, &self.edition, None);
+    }
+}
+
+/// Remember the process started by the launcher until
+/// the returned guard is dropped
+pub fn track_launched(game: impl ToString, edition: impl ToString, pid: u32) -> LaunchedGuard {
+    let guard = LaunchedGuard {
+        game: game.to_string(),
+        edition: edition.to_string()
+    };
+
+    set_launched(&guard.game, &guard.edition, Some(pid));
+
+    guard
+}
+
+/// Remember (or forget) the process started by the launcher
+/// for the given game edition
+fn set_launched(game: impl ToString, edition: impl ToString, pid: Option<u32>) {
+    let key = (game.to_string(), edition.to_string());
+
+    let mut launched = LAUNCHED.write().expect("Failed to lock launched processes");
+
+    match pid.and_then(|pid| ProcessInfo::read(pid).ok()) {
+        Some(process) => {
+            launched.insert(key, LaunchedProcess {
+                pid: process.pid,
+                started_at: process.started_at
+            });
+        }
+
+        None => {
+            launched.remove(&key);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+
+    /// Process name. Windows executable name for wine processes
+    pub name: String,
+
+    pub command: Vec<String>,
+    pub exe: Option<PathBuf>,
+
+    /// Wine prefix of the wine process
+    pub wine_prefix: Option<PathBuf>,
+    pub wineserver: bool,
+
+    /// Process group id
+    pgrp: u32,
+
+    started_at: u64
+}
+
+impl ProcessInfo {
+    /// Read process info from the `/proc` filesystem
+    pub fn read(pid: u32) -> anyhow::Result<Self> {
+        let path = PathBuf::from("/proc").join(pid.to_string());
+
+        // Process name can contain spaces and brackets
+        // so fields are parsed after the last bracket
+        let stat = std::fs::read_to_string(path.join("stat"))?;
+
+        let Some((_, fields)) = stat.rsplit_once(')') else {
+            anyhow::bail!("Failed to parse stat of the process {pid}");
+        };
+
+        let fields = fields.split_whitespace().collect::<Vec<_>>();
+
+        // Fields 4, 5 and 22 of the stat file, starting from the state field
+        let (Some(ppid), Some(pgrp), Some(started_at)) = (fields.get(1), fields.get(2), fields.get(19)) else {
+            anyhow::bail!("Failed to parse stat of the process {pid}");
+        };
+
+        let comm = std::fs::read_to_string(path.join("comm"))?;
+
+        let command = std::fs::read(path.join("cmdline"))?
+            .split(|byte| *byte == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect::<Vec<_>>();
+
+        // Not available for processes of other users
+        let exe = std::fs::read_link(path.join("exe")).ok();
+
+        let exe_name = exe.as_ref()
+            .and_then(|exe| exe.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let wineserver = exe_name == "wineserver" || comm.trim_end() == "wineserver";
+
+        // Wine processes keep windows path of the executable in the first argument
+        let windows_name = command.first()
+            .filter(|name| name.contains('\\') || name.to_ascii_lowercase().ends_with(".exe"))
+            .and_then(|name| name.rsplit(['\\', '/']).next())
+            .map(String::from);
+
+        let is_wine = wineserver || windows_name.is_some() || exe_name.starts_with("wine");
+
+        let wine_prefix = is_wine
+            .then(|| wine_prefix(&path))
+            .flatten();
+
+        Ok(Self {
+            pid,
+            ppid: ppid.parse()?,
+            name: windows_name.unwrap_or_else(|| comm.trim_end().to_string()),
+            command,
+            exe,
+            wine_prefix,
+            wineserver,
+            pgrp: pgrp.parse()?,
+            started_at: started_at.parse()?
+        })
+    }
+
+    /// List all running processes
+    pub fn list() -> anyhow::Result<Vec<Self>> {
+        let mut processes = Vec::new();
+
+        for entry in std::fs::read_dir("/proc")?.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|pid| pid.parse::<u32>().ok()) else {
+                continue;
+            };
+
+            // Process could stop while we were reading it
+            if let Ok(process) = Self::read(pid) {
+                processes.push(process);
+            }
+        }
+
+        Ok(processes)
+    }
+
+    #[inline]
+    /// Check if the process is running in the given wine prefix
+    pub fn is_in_prefix(&self, prefix: &Path) -> bool {
+        match (&self.wine_prefix, prefix.canonicalize()) {
+            (Some(wine_prefix), Ok(prefix)) => wine_prefix == &prefix,
+
+            _ => false
+        }
+    }
+
+    /// Convert process info to the lua table
+    /// 
+    /// Command line can contain secrets, so it's hidden
+    /// unless `with_command` is set
+    fn to_lua<'lua>(&self, lua: &'lua Lua, with_command: bool) -> LuaResult<LuaTable<'lua>> {
+        let process = lua.create_table()?;
+
+        process.set("pid", self.pid)?;
+        process.set("ppid", self.ppid)?;
+        process.set("name", self.name.as_str())?;
+
+        if with_command {
+            process.set("command", self.command.clone())?;
+        } else {
+            process.set("command", lua.create_table()?)?;
+        }
+
+        process.set("exe", self.exe.as_ref().map(|exe| exe.to_string_lossy().to_string()))?;
+        process.set("wine_prefix", self.wine_prefix.as_ref().map(|prefix| prefix.to_string_lossy().to_string()))?;
+        process.set("wineserver", self.wineserver)?;
+
+        Ok(process)
+    }
+}
+
+/// Get wine prefix from the process environment
+fn wine_prefix(path: &Path) -> Option<PathBuf> {
+    let environ = std::fs::read(path.join("environ")).ok()?;
+
+    let prefix = environ.split(|byte| *byte == 0)
+        .find_map(|var| var.strip_prefix(b"WINEPREFIX="))
+        .map(|prefix| PathBuf::from(String::from_utf8_lossy(prefix).to_string()))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".wine")))?;
+
+    Some(prefix.canonicalize().unwrap_or(prefix))
+}
+
+/// Get processes started by the launcher for the given game edition,
+/// including all their children
+/// 
+/// Launched command can exit before the game (e.g. game launchers),
+/// so processes are also found by the process group of the command
+fn launched_tree(processes: &[ProcessInfo], game: &str, edition: &str) -> Vec<ProcessInfo> {
+    let launched = LAUNCHED.read()
+        .expect("Failed to lock launched processes")
+        .get(&(game.to_string(), edition.to_string()))
+        .copied();
+
+    let Some(launched) = launched else {
+        return Vec::new();
+    };
+
+    let mut tree = processes.iter()
+        .filter(|process| process.pgrp == launched.pid)
+        .filter(|process| process.pid != launched.pid || process.started_at == launched.started_at)
+        .map(|process| process.pid)
+        .collect::<HashSet<_>>();
+
+    let root_alive = processes.iter()
+        .any(|process| process.pid == launched.pid && process.started_at == launched.started_at);
+
+    if root_alive {
+        tree.insert(launched.pid);
+    }
+
+    if tree.is_empty() {
+        return Vec::new();
+    }
+
+    // Processes are listed in arbitrary order
+    // so the tree is expanded until it stops changing
+    loop {
+        let children = processes.iter()
+            .filter(|process| !tree.contains(&process.pid) && tree.contains(&process.ppid))
+            .map(|process| process.pid)
+            .collect::<Vec<_>>();
+
+        if children.is_empty() {
+            break;
+        }
+
+        tree.extend(children);
+    }
+
+    processes.iter()
+        .filter(|process| tree.contains(&process.pid))
+        .cloned()
+        .collect()
+}
+
+fn signal_number(signal: Option<LuaValue>) -> LuaResult<i32> {
+    match signal {
+        None | Some(LuaValue::Nil) => Ok(libc::SIGTERM),
+
+        Some(LuaValue::Integer(number)) => match i32::try_from(number) {
+            Ok(signal) if (1..=libc::SIGRTMAX()).contains(&signal) => Ok(signal),
+
+            _ => Err(LuaError::external(format!("Invalid signal number: {number}")))
+        },
+
+        Some(LuaValue::String(signal)) => match signal.to_str()? {
+            "SIGTERM" => Ok(libc::SIGTERM),
+            "SIGKILL" => Ok(libc::SIGKILL),
+            "SIGINT"  => Ok(libc::SIGINT),
+            "SIGHUP"  => Ok(libc::SIGHUP),
+            "SIGSTOP" => Ok(libc::SIGSTOP),
+            "SIGCONT" => Ok(libc::SIGCONT),
+
+            signal => Err(LuaError::external(format!("Unsupported signal: {signal}")))
+        },
+
+        Some(signal) => Err(LuaError::external(format!("Signal must be a number or a string, got {}", signal.type_name())))
+    }
+}
+
+pub fn register(builtins: &Builtins, has_process: bool) -> LuaResult<()> {
+    let lua = builtins.lua();
+
+    // Lua patterns are matched by the original function
+    // so scripts can't change its behavior
+    let find = lua.create_registry_value(lua.globals()
+        .get::<_, LuaTable>("string")?
+        .get::<_, LuaFunction>("find")?)?;
+
+    // Command lines of other processes are only available
+    // with the process capability
+    builtins.set("process.list", lua.create_function(move |lua, _: ()| {
+        let processes = ProcessInfo::list().map_err(LuaError::external)?;
+
+        processes.iter()
+            .map(|process| process.to_lua(lua, has_process))
+            .collect::<LuaResult<Vec<_>>>()
+    })?)?;
+
+    builtins.set("process.find", lua.create_function(move |lua, pattern: String| {
+        let find = lua.registry_value::<LuaFunction>(&find)?;
+
+        let mut found = Vec::new();
+
+        for process in ProcessInfo::list().map_err(LuaError::external)? {
+            if !find.call::<_, LuaValue>((process.name.as_str(), pattern.as_str()))?.is_nil() {
+                found.push(process.to_lua(lua, has_process)?);
+            }
+        }
+
+        Ok(found)
+    })?)?;
+
+    builtins.set("process.wineserver", lua.create_function(move |lua, _: ()| {
+        let prefix = config::get().components.wine.prefix.path;
+
+        ProcessInfo::list()
+            .map_err(LuaError::external)?
+            .into_iter()
+            .find(|process| process.wineserver && process.is_in_prefix(&prefix))
+            .map(|process| process.to_lua(lua, has_process))
+            .transpose()
+    })?)?;
+
+    let game_name = builtins.game_name().to_string();
+
+    builtins.set("process.launched", lua.create_function(move |lua, edition: String| {
+        let processes = ProcessInfo::list().map_err(LuaError::external)?;
+
+        launched_tree(&processes, &game_name, &edition).iter()
+            .map(|process| process.to_lua(lua, true))
+            .collect::<LuaResult<Vec<_>>>()
+    })?)?;
+
+    let game_name = builtins.game_name().to_string();
+
+    builtins.set("process.kill", lua.create_function(move |_, (pid, signal): (u32, Option<LuaValue>)| {
+        let signal = signal_number(signal)?;
+
+        // Pid 0 sends the signal to the whole launcher process group
+        if pid == 0 || pid == std::process::id() {
+            return Err(LuaError::external(format!("Process {pid} can't be killed by the integration script")));
+        }
+
+        // Without the process capability scripts can only kill
+        // processes of the game started by the launcher
+        if !has_process {
+            let processes = ProcessInfo::list().map_err(LuaError::external)?;
+
+            if !processes.iter().any(|process| process.pid == pid) {
+                return Err(LuaError::external(format!("Process {pid} is not running")));
+            }
+
+            let editions = LAUNCHED.read()
+                .expect("Failed to lock launched processes")
+                .keys()
+                .filter(|(game, _)| game == &game_name)
+                .map(|(_, edition)| edition.clone())
+                .collect::<Vec<_>>();
+
+            let allowed = editions.iter().any(|edition| {
+                launched_tree(&processes, &game_name, edition).iter()
+                    .any(|process| process.pid == pid)
+            });
+
+            if !allowed {
+                return Err(LuaError::external(format!("Process {pid} wasn't started by the launcher")));
+            }
+        }
+
+        let Ok(pid) = libc::pid_t::try_from(pid) else {
+            return Err(LuaError::external(format!("Invalid process id: {pid}")));
+        };
+
+        if unsafe { libc::kill(pid, signal) } != 0 {
+            return Err(LuaError::external(std::io::Error::last_os_error()));
+        }
+
+        Ok(())
+    })?)?;
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use serde_json::Value as Json;
+
+use mlua::prelude::*;
+
+use crate::games::storage::{IntegrationStorage, is_valid_edition};
+
+use super::Builtins;
+
+/// Storage files are stored in the edition folder,
+/// so edition name must be a plain folder name
+fn check_edition(edition: &str) -> LuaResult<()> {
+    if !is_valid_edition(edition) {
+        return Err(LuaError::RuntimeError(format!("Invalid game edition name: {edition}")));
+    }
+
+    Ok(())
+}
+
+pub fn register(builtins: &Builtins) -> LuaResult<()> {
+    let lua = builtins.lua();
+
+    let game_name = builtins.game_name().to_string();
+
+    builtins.set("storage.get", lua.create_function(move |lua, (edition, key): (String, String)| {
+        check_edition(&edition)?;
+
+        let storage = IntegrationStorage::load_for_game(&game_name, edition)
+            .map_err(LuaError::external)?;
+
+        match storage.values.get(&key) {
+            Some(value) => lua.to_value(value),
+            None => Ok(LuaNil)
+        }
+    })?)?;
+
+    let game_name = builtins.game_name().to_string();
+
+    builtins.set("storage.set", lua.create_function(move |lua, (edition, key, value): (String, String, LuaValue)| {
+        check_edition(&edition)?;
+
+        let mut storage = IntegrationStorage::load_for_game(&game_name, &edition)
+            .map_err(LuaError::external)?;
+
+        if value.is_nil() {
+            storage.values.remove(&key);
+        } else {
+            storage.values.insert(key, lua.from_value::<Json>(value)?);
+        }
+
+        storage.save_for_game(&game_name, &edition)
+            .map_err(LuaError::external)
+    })?)?;
+
+    let game_name = builtins.game_name().to_string();
+
+    builtins.set("storage.delete", lua.create_function(move |lua, (edition, key): (String, String)| {
+        check_edition(&edition)?;
+
+        let mut storage = IntegrationStorage::load_for_game(&game_name, &edition)
+            .map_err(LuaError::external)?;
+
+        if storage.values.remove(&key).is_some() {
+            storage.save_for_game(&game_name, &edition)
+                .map_err(LuaError::external)?;
+        }
+
+        Ok(())
+    })?)?;
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Access to the network built-ins
+    Network,
+
+    /// Access to the files in the given scope
+    Filesystem(FilesystemScope),
+
+    /// Execution of system processes (`os.execute`, `io.popen`)
+    Process
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilesystemScope {
+    /// Game installation folder
+    Game,
+
+    /// Game addons folder
+    Addons,
+
+    /// Transition folder of the currently running update
+    Transition,
+
+    /// Folder of the integration script itself
+    Integration,
+
+    /// Unrestricted access to the whole filesystem (`io` and `os` libraries)
+    System
+}
+
+impl Capability {
+    /// List of capabilities approved for all integrations by default
+    pub fn default_approved() -> Vec<Self> {
+        vec![
+            Self::Network,
+            Self::Filesystem(FilesystemScope::Game),
+            Self::Filesystem(FilesystemScope::Addons),
+            Self::Filesystem(FilesystemScope::Transition),
+            Self::Filesystem(FilesystemScope::Integration)
+        ]
+    }
+
+    /// Capabilities of the v1 scripts which don't declare them
+    /// in the manifest, made before the sandbox was introduced
+    pub fn legacy() -> Vec<Self> {
+        vec![
+            Self::Network,
+            Self::Filesystem(FilesystemScope::Game),
+            Self::Filesystem(FilesystemScope::Addons),
+            Self::Filesystem(FilesystemScope::Transition),
+            Self::Filesystem(FilesystemScope::Integration)
+        ]
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Network => "network",
+            Self::Process => "process",
+
+            Self::Filesystem(FilesystemScope::Game)        => "filesystem:game",
+            Self::Filesystem(FilesystemScope::Addons)      => "filesystem:addons",
+            Self::Filesystem(FilesystemScope::Transition)  => "filesystem:transition",
+            Self::Filesystem(FilesystemScope::Integration) => "filesystem:integration",
+            Self::Filesystem(FilesystemScope::System)      => "filesystem:system"
+        }
+    }
+}
+
+impl FromStr for Capability {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "network" => Ok(Self::Network),
+            "process" => Ok(Self::Process),
+
+            "filesystem:game"        => Ok(Self::Filesystem(FilesystemScope::Game)),
+            "filesystem:addons"      => Ok(Self::Filesystem(FilesystemScope::Addons)),
+            "filesystem:transition"  => Ok(Self::Filesystem(FilesystemScope::Transition)),
+            "filesystem:integration" => Ok(Self::Filesystem(FilesystemScope::Integration)),
+            "filesystem:system"      => Ok(Self::Filesystem(FilesystemScope::System)),
+
+            _ => anyhow::bail!("Unknown integration capability: '{value}'")
+        }
+    }
+}
+
+impl Serialize for Capability {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.to_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Capability {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        Self::from_str(&value).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use mlua::prelude::*;
+
+use super::Game;
+use super::standards::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub error: Option<String>
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Results of the integration script conformance checks
+pub struct Report {
+    pub checks: Vec<Check>
+}
+
+impl Report {
+    /// Store result of the check and return its value if it succeeded
+    fn check<T>(&mut self, name: impl ToString, result: anyhow::Result<T>) -> Option<T> {
+        let (value, error) = match result {
+            Ok(value) => (Some(value), None),
+            Err(err) => (None, Some(format!("{err:#}")))
+        };
+
+        self.checks.push(Check {
+            name: name.to_string(),
+            error
+        });
+
+        value
+    }
+
+    #[inline]
+    pub fn passed(&self) -> usize {
+        self.checks.iter().filter(|check| check.error.is_none()).count()
+    }
+
+    #[inline]
+    pub fn failed(&self) -> usize {
+        self.checks.len() - self.passed()
+    }
+
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.failed() == 0
+    }
+
+    /// Print pass/fail report to the stdout
+    pub fn print(&self) {
+        for check in &self.checks {
+            match &check.error {
+                None => println!("[PASS] {}", check.name),
+                Some(err) => println!("[FAIL] {}: {err}", check.name)
+            }
+        }
+
+        println!();
+        println!("{} passed, {} failed", self.passed(), self.failed());
+    }
+}
+
+/// Call every required API of the integration script
+/// and validate values returned by it
+///
+/// The integration is loaded with all the capabilities declared
+/// in its manifest. Game and addons folders are used as fixtures
+/// and are not modified by the checks
+pub fn run(manifest_path: impl AsRef<Path>, game_path: impl AsRef<Path>, addons_path: impl AsRef<Path>) -> Report {
+    let mut report = Report::default();
+
+    let game_path = game_path.as_ref().to_string_lossy().to_string();
+    let addons_path = addons_path.as_ref();
+
+    let game = Game::with_capabilities(manifest_path, |manifest| manifest.script_capabilities.clone());
+
+    let Some(game) = report.check("load integration", game) else {
+        return report;
+    };
+
+    let driver = &game.driver;
+
+    let editions = driver.get_game_editions_list().and_then(|editions| {
+        if editions.is_empty() {
+            anyhow::bail!("Editions list is empty");
+        }
+
+        Ok(editions)
+    });
+
+    let Some(editions) = report.check("game.get_editions_list", editions) else {
+        return report;
+    };
+
+    for edition in editions {
+        let edition = edition.name;
+
+        report.check(format!("visual.get_card_picture [{edition}]"), driver.get_card_picture(&edition));
+        report.check(format!("visual.get_background_picture [{edition}]"), driver.get_background_picture(&edition));
+        report.check(format!("visual.get_details_background_css [{edition}]"), driver.get_details_background_style(&edition));
+
+        report.check(format!("game.is_installed [{edition}]"), driver.is_game_installed(&game_path, &edition));
+        report.check(format!("game.get_version [{edition}]"), driver.get_game_version(&game_path, &edition));
+        report.check(format!("game.get_download [{edition}]"), driver.get_game_download(&edition));
+        report.check(format!("game.get_diff [{edition}]"), driver.get_game_diff(&game_path, &edition));
+        report.check(format!("game.get_status [{edition}]"), driver.get_game_status(&game_path, &edition));
+        report.check(format!("game.get_launch_options [{edition}]"), driver.get_launch_options(&game_path, &addons_path.to_string_lossy(), &edition));
+        report.check(format!("game.is_running [{edition}]"), driver.is_process_running(&game_path, &edition));
+        report.check(format!("game.get_integrity_info [{edition}]"), driver.get_game_integrity(&game_path, &edition));
+
+        // Killing the game process is not safe to call in the checks
+        let has_kill = driver.execute(|runtime| runtime.has("v1_game_kill", "game.kill"))
+            .and_then(|has_kill| {
+                if !has_kill {
+                    anyhow::bail!("Function is not defined");
+                }
+
+                Ok(())
+            });
+
+        report.check(format!("game.kill [{edition}]"), has_kill);
+
+        // Invalid addons are silently skipped by the parser,
+        // so their amount is compared with the raw table
+        let addons = driver.get_addons_list(&edition).and_then(|groups| {
+            let expected = driver.execute({
+                let edition = edition.clone();
+
+                move |runtime| {
+                    let mut expected = 0;
+
+                    for group in runtime.call::<_, LuaTable>("v1_addons_get_list", "addons.get_list", edition)?.sequence_values::<LuaTable>() {
+                        expected += group?.get::<_, LuaTable>("addons")?.raw_len();
+                    }
+
+                    Ok(expected)
+                }
+            })?;
+
+            let parsed = groups.iter()
+                .map(|group| group.addons.len())
+                .sum::<usize>();
+
+            if parsed != expected {
+                anyhow::bail!("{} of {expected} addons have wrong format", expected - parsed);
+            }
+
+            Ok(groups)
+        });
+
+        let Some(groups) = report.check(format!("addons.get_list [{edition}]"), addons) else {
+            continue;
+        };
+
+        for group in groups {
+            for addon in group.addons {
+                let name = format!("{}/{} [{edition}]", group.name, addon.name);
+
+                let addon_path = if addon.r#type == AddonType::Module {
+                    game_path.clone()
+                } else {
+                    addons_path.join(&group.name)
+                        .join(&addon.name)
+                        .to_string_lossy()
+                        .to_string()
+                };
+
+                report.check(format!("addons.is_installed {name}"), driver.is_addon_installed(&group.name, &addon.name, &addon_path, &edition));
+                report.check(format!("addons.get_version {name}"), driver.get_addon_version(&group.name, &addon.name, &addon_path, &edition));
+                report.check(format!("addons.get_download {name}"), driver.get_addon_download(&group.name, &addon.name, &edition));
+                report.check(format!("addons.get_diff {name}"), driver.get_addon_diff(&group.name, &addon.name, &addon_path, &edition));
+                report.check(format!("addons.get_paths {name}"), driver.get_addon_paths(&group.name, &addon.name, &addon_path, &edition));
+                report.check(format!("addons.get_integrity_info {name}"), driver.get_addon_integrity(&group.name, &addon.name, &addon_path, &edition));
+            }
+        }
+    }
+
+    report
+}
@@ -1,160 +1,285 @@
-use serde_json::Value as Json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde_json::{Value as Json, Map};
 
 use mlua::prelude::*;
 
+use crate::config;
+
 use super::standards::prelude::*;
+use super::capabilities::{Capability, FilesystemScope};
+use super::builtins::Progress;
+use super::runtime::Runtime;
+
+type Request = Box<dyn FnOnce(&Runtime) + Send>;
+
+/// Max time UI thread calls wait for the integration thread
+/// to start processing the request
+pub const QUEUE_TIMEOUT: Duration = Duration::from_secs(10);
+
+const REQUEST_QUEUED: u8 = 0;
+const REQUEST_STARTED: u8 = 1;
+const REQUEST_CANCELLED: u8 = 2;
+
+lazy_static::lazy_static! {
+    /// Cached results generations of every game
+    ///
+    /// Results are cached together with the current generation,
+    /// so increasing it makes results of the calls which were
+    /// running during the invalidation unreachable
+    static ref CACHE_GENERATIONS: RwLock<HashMap<String, u64>> = RwLock::new(HashMap::new());
+}
+
+/// Cached results generation shared by all the games
+static GLOBAL_CACHE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Invalidate cached results of the given game integration
+///
+/// Outdated results are not removed from the cache stores
+/// but can't be reached anymore, so cached results of other
+/// games stay valid. They expire together with the rest of the cache
+pub fn invalidate_cache(game_name: impl AsRef<str>) {
+    *CACHE_GENERATIONS.write()
+        .expect("Failed to lock cache generations")
+        .entry(game_name.as_ref().to_string())
+        .or_default() += 1;
+}
+
+#[inline]
+/// Invalidate cached results of all the game integrations
+pub fn invalidate_all_caches() {
+    GLOBAL_CACHE_GENERATION.fetch_add(1, Ordering::Relaxed);
+
+    cache::clear();
+}
 
-#[derive(Debug)]
+/// Get key of the cached API call result
+fn cache_key(game_name: &str, args: &str) -> String {
+    let generation = CACHE_GENERATIONS.read()
+        .expect("Failed to lock cache generations")
+        .get(game_name)
+        .copied()
+        .unwrap_or_default();
+
+    format!("{game_name}:{}:{generation}:{args}", GLOBAL_CACHE_GENERATION.load(Ordering::Relaxed))
+}
+
+#[derive(Debug, Clone)]
+/// Proxy to the integration script
+///
+/// Lua state of every integration lives on its own thread,
+/// and the driver's methods send requests to it
 pub struct Driver {
     pub game_name: String,
     pub standard: IntegrationStandard,
-    pub lua: Lua
+
+    sender: Arc<RwLock<flume::Sender<Request>>>,
+    progress: Progress,
+
+    /// Max time to wait for the request to be started
+    queue_timeout: Option<Duration>
 }
 
 impl Driver {
-    pub fn new(game_name: impl ToString, standard: IntegrationStandard, script: impl AsRef<str>) -> anyhow::Result<Self> {
-        let lua = Lua::new();
-
-        match standard {
-            IntegrationStandard::V1 => {
-                lua.globals().set("v1_network_fetch", lua.create_function(|lua, (uri, options): (String, Option<RequestOptions>)| {
-                    let (method, headers, body, timeout) = match options {
-                        Some(options) => {
-                            let method = options.method.unwrap_or(RequestMethod::Get);
+    pub fn new(
+        game_name: impl ToString,
+        standard: IntegrationStandard,
+        capabilities: &[Capability],
+        integration_path: impl AsRef<Path>,
+        script: impl ToString
+    ) -> anyhow::Result<Self> {
+        let game_name = game_name.to_string();
+        let progress = Progress::default();
+
+        let sender = Self::spawn(&game_name, standard, capabilities, integration_path.as_ref(), script.to_string(), &progress)?;
 
-                            (method, options.headers, options.body, options.timeout)
-                        }
+        Ok(Self {
+            game_name,
+            standard,
+            sender: Arc::new(RwLock::new(sender)),
+            progress,
+            queue_timeout: None
+        })
+    }
 
-                        None => (RequestMethod::Get, None, None, None)
-                    };
+    /// Spawn integration thread and return sender of its requests
+    fn spawn(
+        game_name: &str,
+        standard: IntegrationStandard,
+        capabilities: &[Capability],
+        integration_path: &Path,
+        script: String,
+        progress: &Progress
+    ) -> anyhow::Result<flume::Sender<Request>> {
+        let (sender, receiver) = flume::unbounded::<Request>();
+        let (init_sender, init_receiver) = flume::bounded(1);
+
+        let thread_game_name = game_name.to_string();
+        let capabilities = capabilities.to_vec();
+        let integration_path = PathBuf::from(integration_path);
+        let progress = progress.clone();
+
+        std::thread::Builder::new()
+            .name(format!("integration:{game_name}"))
+            .spawn(move || {
+                let runtime = match Runtime::new(thread_game_name, standard, &capabilities, integration_path, script, progress) {
+                    Ok(runtime) => {
+                        let _ = init_sender.send(Ok(()));
+
+                        runtime
+                    }
 
-                    let mut request = anime_game_core::network::minreq::Request::new(method, uri);
+                    Err(err) => {
+                        let _ = init_sender.send(Err(err));
 
-                    if let Some(headers) = headers {
-                        for (key, value) in headers {
-                            request = request.with_header(key, value);
-                        }
+                        return;
                     }
+                };
 
-                    if let Some(body) = body {
-                        request = request.with_body(body);
-                    }
+                // Process requests until all the proxies are dropped
+                while let Ok(request) = receiver.recv() {
+                    request(&runtime);
+                }
+            })?;
+
+        init_receiver.recv()??;
+
+        Ok(sender)
+    }
+
+    /// Replace lua state of the integration with a new one
+    ///
+    /// Requests sent before the reload are finished by the previous state.
+    /// Standard of the integration script can't be changed
+    pub fn reload(
+        &self,
+        capabilities: &[Capability],
+        integration_path: impl AsRef<Path>,
+        script: impl ToString
+    ) -> anyhow::Result<()> {
+        let sender = Self::spawn(&self.game_name, self.standard, capabilities, integration_path.as_ref(), script.to_string(), &self.progress)?;
+
+        *self.sender.write().expect("Failed to lock integration sender") = sender;
+
+        invalidate_cache(&self.game_name);
+
+        Ok(())
+    }
+
+    /// Send request to the integration thread
+    ///
+    /// Returned receiver can be used to wait for the result
+    /// without blocking the caller
+    pub fn request<T: Send + 'static>(
+        &self,
+        request: impl FnOnce(&Runtime) -> anyhow::Result<T> + Send + 'static
+    ) -> anyhow::Result<flume::Receiver<anyhow::Result<T>>> {
+        let (sender, receiver) = flume::bounded(1);
+
+        self.sender.read()
+            .expect("Failed to lock integration sender")
+            .send(Box::new(move |runtime| {
+                let _ = sender.send(request(runtime));
+            }))
+            .map_err(|_| anyhow::anyhow!("Integration script thread of {} is stopped", self.game_name))?;
+
+        Ok(receiver)
+    }
+
+    /// Get driver proxy which cancels requests not started in the given time
+    ///
+    /// Requests are processed one by one, so a long running call
+    /// (e.g. transition hook) blocks all the others. Calls made from
+    /// the UI thread should use it to not freeze the launcher
+    pub fn with_queue_timeout(&self, timeout: Duration) -> Self {
+        Self {
+            queue_timeout: Some(timeout),
+            ..self.clone()
+        }
+    }
+
+    /// Send request to the integration thread and wait for its result
+    ///
+    /// If the queue timeout is set and the request wasn't started
+    /// in time it's cancelled and an error is returned. Otherwise the
+    /// request waits for all the previous ones to finish. Started
+    /// requests are limited by their execution budget
+    pub fn execute<T: Send + 'static>(
+        &self,
+        request: impl FnOnce(&Runtime) -> anyhow::Result<T> + Send + 'static
+    ) -> anyhow::Result<T> {
+        let Some(queue_timeout) = self.queue_timeout else {
+            return self.request(request)?.recv()?;
+        };
+
+        let state = Arc::new(AtomicU8::new(REQUEST_QUEUED));
+
+        let receiver = {
+            let state = state.clone();
+
+            self.request(move |runtime| {
+                if state.compare_exchange(REQUEST_QUEUED, REQUEST_STARTED, Ordering::AcqRel, Ordering::Acquire).is_err() {
+                    anyhow::bail!("Request was cancelled");
+                }
 
-                    if let Some(timeout) = timeout {
-                        request = request.with_timeout(timeout);
-                    }
+                request(runtime)
+            })?
+        };
+
+        match receiver.recv_timeout(queue_timeout) {
+            Ok(result) => result,
 
-                    request.send()
-                        .map(|response| {
-                            let result = lua.create_table()?;
-                            let headers = lua.create_table()?;
-
-                            for (key, value) in &response.headers {
-                                headers.set(key.as_str(), value.as_str())?;
-                            }
-
-                            result.set("url", response.url.as_str())?;
-                            result.set("status", response.status_code)?;
-                            result.set("statusText", response.reason_phrase.as_str())?;
-                            result.set("ok", (200..300).contains(&response.status_code))?;
-                            result.set("headers", headers)?;
-                            result.set("body", lua.create_string(response.as_bytes())?)?;
-
-                            result.set("json", lua.create_function(move |lua, _: ()| {
-                                response.json::<Json>()
-                                    .map(|value| lua.to_value(&value))
-                                    .map_err(LuaError::external)
-                            })?)?;
-
-                            Ok::<LuaTable<'_>, mlua::Error>(result)
-                        })
-                        .map_err(LuaError::external)
-                })?)?;
-        
-                lua.globals().set("v1_json_decode", lua.create_function(|lua, json: String| {
-                    serde_json::from_str::<Json>(&json)
-                        .map(|value| lua.to_value(&value))
-                        .map_err(LuaError::external)
-                })?)?;
+            Err(flume::RecvTimeoutError::Timeout) => {
+                if state.compare_exchange(REQUEST_QUEUED, REQUEST_CANCELLED, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+                    anyhow::bail!("Integration script of {} is busy processing another request", self.game_name);
+                }
+
+                receiver.recv()?
             }
+
+            Err(flume::RecvTimeoutError::Disconnected) => anyhow::bail!("Integration script thread of {} is stopped", self.game_name)
         }
+    }
 
-        lua.load(script.as_ref()).exec()?;
+    /// Run given closure reporting progress of the built-in
+    /// functions (e.g. archives extraction) to the callback
+    pub fn with_progress<T>(&self, callback: impl Fn(u64, u64) + Send + 'static, f: impl FnOnce(&Self) -> T) -> T {
+        self.progress.set(Some(Box::new(callback)));
 
-        Ok(Self {
-            game_name: game_name.to_string(),
-            standard,
-            lua
-        })
-    }
-}
+        let result = f(self);
 
-// Let (at least for now) lua scripts maintainers resolve
-// possible data races themselves
-// 
-// FIXME: use Mutex or RwLock, or anything else but please remove this shit
+        self.progress.set(None);
 
-unsafe impl Send for Driver {}
-unsafe impl Sync for Driver {}
+        result
+    }
+}
 
 impl Driver {
     #[inline]
     pub fn get_card_picture(&self, edition: &str) -> anyhow::Result<String> {
-        #[inline]
-        #[cached::proc_macro::cached(
-            key = "String",
-            convert = r##"{ format!("{_game}{edition}") }"##,
-            result
-        )]
-        fn get_card_picture(lua: &Lua, standard: IntegrationStandard, _game: &str, edition: &str) -> anyhow::Result<String> {
-            match standard {
-                IntegrationStandard::V1 => Ok(lua.globals().call_function("v1_visual_get_card_picture", edition)?)
-            }
-        }
-
-        get_card_picture(&self.lua, self.standard, &self.game_name, edition)
+        cache::get_card_picture(self, &self.game_name, edition)
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_background_picture(&self, edition: &str) -> anyhow::Result<String> {
-        #[inline]
-        #[cached::proc_macro::cached(
-            key = "String",
-            convert = r##"{ format!("{_game}{edition}") }"##,
-            result
-        )]
-        fn get_background_picture(lua: &Lua, standard: IntegrationStandard, _game: &str, edition: &str) -> anyhow::Result<String> {
-            match standard {
-                IntegrationStandard::V1 => Ok(lua.globals().call_function("v1_visual_get_background_picture", edition)?)
-            }
-        }
-
-        get_background_picture(&self.lua, self.standard, &self.game_name, edition)
+        cache::get_background_picture(self, &self.game_name, edition)
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_details_background_style(&self, edition: &str) -> anyhow::Result<Option<String>> {
-        #[inline]
-        #[cached::proc_macro::cached(
-            key = "String",
-            convert = r##"{ format!("{_game}{edition}") }"##,
-            result
-        )]
-        fn get_details_background_style(lua: &Lua, standard: IntegrationStandard, _game: &str, edition: &str) -> anyhow::Result<Option<String>> {
-            match standard {
-                IntegrationStandard::V1 => {
-                    if !lua.globals().contains_key("v1_visual_get_details_background_css")? {
-                        return Ok(None);
-                    }
-
-                    Ok(lua.globals().call_function("v1_visual_get_details_background_css", edition)?)
-                }
-            }
-        }
+        cache::get_details_background_style(self, &self.game_name, edition)
+    }
 
-        get_details_background_style(&self.lua, self.standard, &self.game_name, edition)
+    #[inline]
+    #[tracing::instrument(level = "trace", ret)]
+    pub fn get_news(&self, edition: &str) -> anyhow::Result<Vec<News>> {
+        cache::get_news(self, &self.game_name, edition)
     }
 
     // Game
@@ -162,139 +287,192 @@ impl Driver {
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_game_editions_list(&self) -> anyhow::Result<Vec<GameEdition>> {
-        #[inline]
-        #[cached::proc_macro::cached(
-            key = "String",
-            convert = r##"{ _game.to_string() }"##,
-            result
-        )]
-        fn get_game_editions_list(lua: &Lua, standard: IntegrationStandard, _game: &str) -> anyhow::Result<Vec<GameEdition>> {
-            match standard {
-                IntegrationStandard::V1 => {
-                    let editions = lua.globals()
-                        .call_function::<_, LuaTable>("v1_game_get_editions_list", ())?
-                        .sequence_values::<LuaTable>()
-                        .flatten()
-                        .map(|edition| GameEdition::from_table(edition, standard))
-                        .collect::<Result<Vec<_>, _>>()?;
-
-                    Ok(editions)
-                }
-            }
-        }
-
-        get_game_editions_list(&self.lua, self.standard, &self.game_name)
+        cache::get_game_editions_list(self, &self.game_name)
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn is_game_installed(&self, path: &str, edition: &str) -> anyhow::Result<bool> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_game_is_installed", (path, edition))?)
-        }
+        let path = path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Game, path.as_str())], "v1_game_is_installed", "game.is_installed", (path.as_str(), edition))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_game_version(&self, path: &str, edition: &str) -> anyhow::Result<Option<String>> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_game_get_version", (path, edition))?)
-        }
+        let path = path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Game, path.as_str())], "v1_game_get_version", "game.get_version", (path.as_str(), edition))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_game_download(&self, edition: &str) -> anyhow::Result<Download> {
-        match self.standard {
-            IntegrationStandard::V1 => {
-                let download = self.lua.globals()
-                    .call_function("v1_game_get_download", edition)?;
+        let edition = edition.to_string();
 
-                Download::from_table(download, self.standard)
-            }
-        }
+        self.execute(move |runtime| {
+            let download = runtime.call("v1_game_get_download", "game.get_download", edition)?;
+
+            Download::from_table(download, runtime.standard)
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_game_diff(&self, path: &str, edition: &str) -> anyhow::Result<Option<Diff>> {
-        match self.standard {
-            IntegrationStandard::V1 => {
-                let diff = self.lua.globals()
-                    .call_function("v1_game_get_diff", (path, edition))?;
-
-                match diff {
-                    Some(diff) => Ok(Some(Diff::from_table(diff, self.standard)?)),
-                    None => Ok(None)
-                }
+        let path = path.to_string();
+        let edition = edition.to_string();
+
+        let settings = self.get_settings_values(&edition)?;
+
+        self.execute(move |runtime| {
+            let settings = runtime.lua.to_value(&settings)?;
+
+            let diff = runtime.call_scoped(&[(FilesystemScope::Game, path.as_str())], "v1_game_get_diff", "game.get_diff", (path.as_str(), edition, settings))?;
+
+            match diff {
+                Some(diff) => Ok(Some(Diff::from_table(diff, runtime.standard)?)),
+                None => Ok(None)
             }
-        }
+        })
+    }
+
+    #[inline]
+    #[tracing::instrument(level = "trace", ret)]
+    pub fn get_game_predownload(&self, path: &str, edition: &str) -> anyhow::Result<Option<Diff>> {
+        let path = path.to_string();
+        let edition = edition.to_string();
+
+        let settings = self.get_settings_values(&edition)?;
+
+        self.execute(move |runtime| {
+            if !runtime.has("v1_game_get_predownload", "game.get_predownload")? {
+                return Ok(None);
+            }
+
+            let settings = runtime.lua.to_value(&settings)?;
+
+            let diff = runtime.call_scoped(&[(FilesystemScope::Game, path.as_str())], "v1_game_get_predownload", "game.get_predownload", (path.as_str(), edition, settings))?;
+
+            match diff {
+                Some(diff) => Ok(Some(Diff::from_table(diff, runtime.standard)?)),
+                None => Ok(None)
+            }
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_game_status(&self, path: &str, edition: &str) -> anyhow::Result<Option<GameStatus>> {
-        match self.standard {
-            IntegrationStandard::V1 => {
-                let status = self.lua.globals()
-                    .call_function("v1_game_get_status", (path, edition))?;
-
-                match status {
-                    Some(status) => Ok(Some(GameStatus::from_table(status, self.standard)?)),
-                    None => Ok(None)
-                }
+        let path = path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            let status = runtime.call_scoped(&[(FilesystemScope::Game, path.as_str())], "v1_game_get_status", "game.get_status", (path.as_str(), edition))?;
+
+            match status {
+                Some(status) => Ok(Some(GameStatus::from_table(status, runtime.standard)?)),
+                None => Ok(None)
             }
-        }
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_launch_options(&self, game_path: &str, addons_path: &str, edition: &str) -> anyhow::Result<GameLaunchOptions> {
-        match self.standard {
-            IntegrationStandard::V1 => {
-                let options = self.lua.globals()
-                    .call_function("v1_game_get_launch_options", (game_path, addons_path, edition))?;
+        let game_path = game_path.to_string();
+        let addons_path = addons_path.to_string();
+        let edition = edition.to_string();
 
-                GameLaunchOptions::from_table(options, self.standard)
-            }
-        }
+        let settings = self.get_settings_values(&edition)?;
+
+        self.execute(move |runtime| {
+            let scopes = [
+                (FilesystemScope::Game, game_path.as_str()),
+                (FilesystemScope::Addons, addons_path.as_str())
+            ];
+
+            let settings = runtime.lua.to_value(&settings)?;
+
+            let options = runtime.call_scoped(&scopes, "v1_game_get_launch_options", "game.get_launch_options", (game_path.as_str(), addons_path.as_str(), edition, settings))?;
+
+            GameLaunchOptions::from_table(options, runtime.standard)
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn is_process_running(&self, game_path: &str, edition: &str) -> anyhow::Result<bool> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_game_is_running", (game_path, edition))?)
-        }
+        let game_path = game_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Game, game_path.as_str())], "v1_game_is_running", "game.is_running", (game_path.as_str(), edition))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn kill_process(&self, game_path: &str, edition: &str) -> anyhow::Result<()> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_game_kill", (game_path, edition))?)
-        }
+        let game_path = game_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Game, game_path.as_str())], "v1_game_kill", "game.kill", (game_path.as_str(), edition))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace")]
     pub fn get_game_integrity(&self, game_path: &str, edition: &str) -> anyhow::Result<Vec<IntegrityInfo>> {
-        match self.standard {
-            IntegrationStandard::V1 => {
-                let info = self.lua.globals()
-                    .call_function::<_, LuaTable>("v1_game_get_integrity_info", (game_path, edition))?
-                    .sequence_values::<LuaTable>()
-                    .flatten()
-                    .map(|info| IntegrityInfo::from_table(info, self.standard))
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                Ok(info)
-            }
-        }
+        let game_path = game_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            let info = runtime.call_scoped::<_, LuaTable>(&[(FilesystemScope::Game, game_path.as_str())], "v1_game_get_integrity_info", "game.get_integrity_info", (game_path.as_str(), edition))?
+                .sequence_values::<LuaTable>()
+                .flatten()
+                .map(|info| IntegrityInfo::from_table(info, runtime.standard))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(info)
+        })
+    }
+
+    // Settings
+
+    #[inline]
+    #[tracing::instrument(level = "trace", ret)]
+    pub fn get_settings_schema(&self, edition: &str) -> anyhow::Result<Vec<Setting>> {
+        cache::get_settings_schema(self, &self.game_name, edition)
+    }
+
+    /// Get values of the integration settings chosen by the user
+    ///
+    /// Options which weren't changed, or which values don't fit
+    /// the current schema, are set to their default values
+    pub fn get_settings_values(&self, edition: &str) -> anyhow::Result<Map<String, Json>> {
+        let mut stored = config::get().games.get_game_values(&self.game_name, edition);
+
+        let values = self.get_settings_schema(edition)?
+            .into_iter()
+            .map(|setting| {
+                let value = stored.remove(&setting.name)
+                    .filter(|value| setting.accepts(value))
+                    .unwrap_or(setting.default);
+
+                (setting.name, value)
+            })
+            .collect();
+
+        Ok(values)
     }
 
     // Addons
@@ -302,130 +480,129 @@ impl Driver {
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_addons_list(&self, edition: &str) -> anyhow::Result<Vec<AddonsGroup>> {
-        #[inline]
-        #[cached::proc_macro::cached(
-            key = "String",
-            convert = r##"{ format!("{_game}{edition}") }"##,
-            result
-        )]
-        fn get_addons_list(lua: &Lua, standard: IntegrationStandard, _game: &str, edition: &str) -> anyhow::Result<Vec<AddonsGroup>> {
-            match standard {
-                IntegrationStandard::V1 => {
-                    let addons = lua.globals()
-                        .call_function::<_, LuaTable>("v1_addons_get_list", edition)?
-                        .sequence_values::<LuaTable>()
-                        .flatten()
-                        .map(|group| AddonsGroup::from_table(group, standard))
-                        .collect::<Result<Vec<_>, _>>()?;
-
-                    Ok(addons)
-                }
-            }
-        }
-
-        get_addons_list(&self.lua, self.standard, &self.game_name, edition)
+        cache::get_addons_list(self, &self.game_name, edition)
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn is_addon_installed(&self, group_name: &str, addon_name: &str, addon_path: &str, edition: &str) -> anyhow::Result<bool> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_addons_is_installed", (
-                    group_name,
-                    addon_name,
-                    addon_path,
-                    edition
-                ))?)
-        }
+        let group_name = group_name.to_string();
+        let addon_name = addon_name.to_string();
+        let addon_path = addon_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Addons, addon_path.as_str())], "v1_addons_is_installed", "addons.is_installed", (
+                group_name,
+                addon_name,
+                addon_path.as_str(),
+                edition
+            ))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_addon_version(&self, group_name: &str, addon_name: &str, addon_path: &str, edition: &str) -> anyhow::Result<Option<String>> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_addons_get_version", (
-                    group_name,
-                    addon_name,
-                    addon_path,
-                    edition
-                ))?)
-        }
+        let group_name = group_name.to_string();
+        let addon_name = addon_name.to_string();
+        let addon_path = addon_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Addons, addon_path.as_str())], "v1_addons_get_version", "addons.get_version", (
+                group_name,
+                addon_name,
+                addon_path.as_str(),
+                edition
+            ))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_addon_download(&self, group_name: &str, addon_name: &str, edition: &str) -> anyhow::Result<Download> {
-        match self.standard {
-            IntegrationStandard::V1 => {
-                let download = self.lua.globals()
-                    .call_function("v1_addons_get_download", (
-                        group_name,
-                        addon_name,
-                        edition
-                    ))?;
-
-                Download::from_table(download, self.standard)
-            }
-        }
+        let group_name = group_name.to_string();
+        let addon_name = addon_name.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            let download = runtime.call("v1_addons_get_download", "addons.get_download", (
+                group_name,
+                addon_name,
+                edition
+            ))?;
+
+            Download::from_table(download, runtime.standard)
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_addon_diff(&self, group_name: &str, addon_name: &str, addon_path: &str, edition: &str) -> anyhow::Result<Option<Diff>> {
-        match self.standard {
-            IntegrationStandard::V1 => {
-                let diff = self.lua.globals()
-                    .call_function("v1_addons_get_diff", (
-                        group_name,
-                        addon_name,
-                        addon_path,
-                        edition
-                    ))?;
-
-                match diff {
-                    Some(diff) => Ok(Some(Diff::from_table(diff, self.standard)?)),
-                    None => Ok(None)
-                }
+        let group_name = group_name.to_string();
+        let addon_name = addon_name.to_string();
+        let addon_path = addon_path.to_string();
+        let edition = edition.to_string();
+
+        let settings = self.get_settings_values(&edition)?;
+
+        self.execute(move |runtime| {
+            let diff = runtime.call_scoped(&[(FilesystemScope::Addons, addon_path.as_str())], "v1_addons_get_diff", "addons.get_diff", (
+                group_name,
+                addon_name,
+                addon_path.as_str(),
+                edition,
+                runtime.lua.to_value(&settings)?
+            ))?;
+
+            match diff {
+                Some(diff) => Ok(Some(Diff::from_table(diff, runtime.standard)?)),
+                None => Ok(None)
             }
-        }
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn get_addon_paths(&self, group_name: &str, addon_name: &str, addon_path: &str, edition: &str) -> anyhow::Result<Vec<String>> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_addons_get_paths", (
-                    group_name,
-                    addon_name,
-                    addon_path,
-                    edition
-                ))?)
-        }
+        let group_name = group_name.to_string();
+        let addon_name = addon_name.to_string();
+        let addon_path = addon_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Addons, addon_path.as_str())], "v1_addons_get_paths", "addons.get_paths", (
+                group_name,
+                addon_name,
+                addon_path.as_str(),
+                edition
+            ))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace")]
     pub fn get_addon_integrity(&self, group_name: &str, addon_name: &str, addon_path: &str, edition: &str) -> anyhow::Result<Vec<IntegrityInfo>> {
-        match self.standard {
-            IntegrationStandard::V1 => {
-                let info = self.lua.globals()
-                    .call_function::<_, LuaTable>("v1_addons_get_integrity_info", (
-                        group_name,
-                        addon_name,
-                        addon_path,
-                        edition
-                    ))?
-                    .sequence_values::<LuaTable>()
-                    .flatten()
-                    .map(|info| IntegrityInfo::from_table(info, self.standard))
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                Ok(info)
-            }
-        }
+        let group_name = group_name.to_string();
+        let addon_name = addon_name.to_string();
+        let addon_path = addon_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            let info = runtime.call_scoped::<_, LuaTable>(&[(FilesystemScope::Addons, addon_path.as_str())], "v1_addons_get_integrity_info", "addons.get_integrity_info", (
+                    group_name,
+                    addon_name,
+                    addon_path.as_str(),
+                    edition
+                ))?
+                .sequence_values::<LuaTable>()
+                .flatten()
+                .map(|info| IntegrityInfo::from_table(info, runtime.standard))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(info)
+        })
     }
 
     // Game transitions
@@ -433,52 +610,88 @@ impl Driver {
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn has_game_diff_pre_transition(&self) -> anyhow::Result<bool> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals().contains_key("v1_game_diff_pre_transition")?)
-        }
+        self.execute(|runtime| runtime.has("v1_game_diff_pre_transition", "hooks.game_diff_pre_transition"))
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn run_game_diff_pre_transition(&self, path: &str, edition: &str) -> anyhow::Result<()> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_game_diff_pre_transition", (path, edition))?)
-        }
+        let path = path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Game, path.as_str())], "v1_game_diff_pre_transition", "hooks.game_diff_pre_transition", (path.as_str(), edition))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn has_game_diff_transition(&self) -> anyhow::Result<bool> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals().contains_key("v1_game_diff_transition")?)
-        }
+        self.execute(|runtime| runtime.has("v1_game_diff_transition", "hooks.game_diff_transition"))
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn run_game_diff_transition(&self, transition_path: &str, edition: &str) -> anyhow::Result<()> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_game_diff_transition", (transition_path, edition))?)
-        }
+        let transition_path = transition_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Transition, transition_path.as_str())], "v1_game_diff_transition", "hooks.game_diff_transition", (transition_path.as_str(), edition))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn has_game_diff_post_transition(&self) -> anyhow::Result<bool> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals().contains_key("v1_game_diff_post_transition")?)
-        }
+        self.execute(|runtime| runtime.has("v1_game_diff_post_transition", "hooks.game_diff_post_transition"))
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn run_game_diff_post_transition(&self, path: &str, edition: &str) -> anyhow::Result<()> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_game_diff_post_transition", (path, edition))?)
-        }
+        let path = path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Game, path.as_str())], "v1_game_diff_post_transition", "hooks.game_diff_post_transition", (path.as_str(), edition))
+        })
+    }
+
+    // Game launch
+
+    #[inline]
+    #[tracing::instrument(level = "trace", ret)]
+    pub fn has_game_pre_launch(&self) -> anyhow::Result<bool> {
+        self.execute(|runtime| runtime.has("v1_game_pre_launch", "hooks.game_pre_launch"))
+    }
+
+    #[inline]
+    #[tracing::instrument(level = "trace", ret)]
+    pub fn run_game_pre_launch(&self, path: &str, edition: &str) -> anyhow::Result<()> {
+        let path = path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Game, path.as_str())], "v1_game_pre_launch", "hooks.game_pre_launch", (path.as_str(), edition))
+        })
+    }
+
+    #[inline]
+    #[tracing::instrument(level = "trace", ret)]
+    pub fn has_game_post_launch(&self) -> anyhow::Result<bool> {
+        self.execute(|runtime| runtime.has("v1_game_post_launch", "hooks.game_post_launch"))
+    }
+
+    #[inline]
+    #[tracing::instrument(level = "trace", ret)]
+    pub fn run_game_post_launch(&self, path: &str, edition: &str, exit_code: Option<i32>, playtime: u64) -> anyhow::Result<()> {
+        let path = path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Game, path.as_str())], "v1_game_post_launch", "hooks.game_post_launch", (path.as_str(), edition, exit_code, playtime))
+        })
     }
 
     // Addons transitions
@@ -486,67 +699,73 @@ impl Driver {
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn has_addons_diff_pre_transition(&self) -> anyhow::Result<bool> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals().contains_key("v1_addons_diff_pre_transition")?)
-        }
+        self.execute(|runtime| runtime.has("v1_addons_diff_pre_transition", "hooks.addons_diff_pre_transition"))
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn run_addons_diff_pre_transition(&self, group_name: &str, addon_name: &str, addon_path: &str, edition: &str) -> anyhow::Result<()> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_addons_diff_pre_transition", (
-                    group_name,
-                    addon_name,
-                    addon_path,
-                    edition
-                ))?)
-        }
+        let group_name = group_name.to_string();
+        let addon_name = addon_name.to_string();
+        let addon_path = addon_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Addons, addon_path.as_str())], "v1_addons_diff_pre_transition", "hooks.addons_diff_pre_transition", (
+                group_name,
+                addon_name,
+                addon_path.as_str(),
+                edition
+            ))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn has_addons_diff_transition(&self) -> anyhow::Result<bool> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals().contains_key("v1_addons_diff_transition")?)
-        }
+        self.execute(|runtime| runtime.has("v1_addons_diff_transition", "hooks.addons_diff_transition"))
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn run_addons_diff_transition(&self, group_name: &str, addon_name: &str, transition_path: &str, edition: &str) -> anyhow::Result<()> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_addons_diff_transition", (
-                    group_name,
-                    addon_name,
-                    transition_path,
-                    edition
-                ))?)
-        }
+        let group_name = group_name.to_string();
+        let addon_name = addon_name.to_string();
+        let transition_path = transition_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Transition, transition_path.as_str())], "v1_addons_diff_transition", "hooks.addons_diff_transition", (
+                group_name,
+                addon_name,
+                transition_path.as_str(),
+                edition
+            ))
+        })
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn has_addons_diff_post_transition(&self) -> anyhow::Result<bool> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals().contains_key("v1_addons_diff_post_transition")?)
-        }
+        self.execute(|runtime| runtime.has("v1_addons_diff_post_transition", "hooks.addons_diff_post_transition"))
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn run_addons_diff_post_transition(&self, group_name: &str, addon_name: &str, addon_path: &str, edition: &str) -> anyhow::Result<()> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_addons_diff_post_transition", (
-                    group_name,
-                    addon_name,
-                    addon_path,
-                    edition
-                ))?)
-        }
+        let group_name = group_name.to_string();
+        let addon_name = addon_name.to_string();
+        let addon_path = addon_path.to_string();
+        let edition = edition.to_string();
+
+        self.execute(move |runtime| {
+            runtime.call_scoped(&[(FilesystemScope::Addons, addon_path.as_str())], "v1_addons_diff_post_transition", "hooks.addons_diff_post_transition", (
+                group_name,
+                addon_name,
+                addon_path.as_str(),
+                edition
+            ))
+        })
     }
 
     // Hashes
@@ -554,20 +773,177 @@ impl Driver {
     #[inline]
     #[tracing::instrument(level = "trace", ret)]
     pub fn has_integrity_hash(&self) -> anyhow::Result<bool> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals().contains_key("v1_integrity_hash")?)
-        }
+        self.execute(|runtime| runtime.has("v1_integrity_hash", "hooks.integrity_hash"))
     }
 
     #[inline]
     #[tracing::instrument(level = "trace", skip(data), ret)]
-    pub fn integrity_hash(&self, algorithm: &str, data: impl AsRef<[u8]>) -> anyhow::Result<String> {
-        match self.standard {
-            IntegrationStandard::V1 => Ok(self.lua.globals()
-                .call_function("v1_integrity_hash", (
-                    algorithm,
-                    self.lua.create_string(data)?
-                ))?)
-        }
+    pub fn integrity_hash(&self, algorithm: &str, data: impl Into<Vec<u8>>) -> anyhow::Result<String> {
+        let algorithm = algorithm.to_string();
+        let data = data.into();
+
+        self.execute(move |runtime| {
+            runtime.call("v1_integrity_hash", "hooks.integrity_hash", (
+                algorithm,
+                runtime.lua.create_string(data)?
+            ))
+        })
+    }
+}
+
+/// Cached results of the integration API calls
+mod cache {
+    use cached::Cached;
+
+    use super::*;
+
+    #[inline]
+    #[cached::proc_macro::cached(
+        key = "String",
+        convert = r##"{ cache_key(_game, edition) }"##,
+        time = 3600,
+        result
+    )]
+    pub fn get_card_picture(driver: &Driver, _game: &str, edition: &str) -> anyhow::Result<String> {
+        let edition = edition.to_string();
+
+        driver.execute(move |runtime| {
+            runtime.call("v1_visual_get_card_picture", "visual.get_card_picture", edition)
+        })
+    }
+
+    #[inline]
+    #[cached::proc_macro::cached(
+        key = "String",
+        convert = r##"{ cache_key(_game, edition) }"##,
+        time = 3600,
+        result
+    )]
+    pub fn get_background_picture(driver: &Driver, _game: &str, edition: &str) -> anyhow::Result<String> {
+        let edition = edition.to_string();
+
+        driver.execute(move |runtime| {
+            runtime.call("v1_visual_get_background_picture", "visual.get_background_picture", edition)
+        })
+    }
+
+    #[inline]
+    #[cached::proc_macro::cached(
+        key = "String",
+        convert = r##"{ cache_key(_game, edition) }"##,
+        time = 3600,
+        result
+    )]
+    pub fn get_details_background_style(driver: &Driver, _game: &str, edition: &str) -> anyhow::Result<Option<String>> {
+        let edition = edition.to_string();
+
+        driver.execute(move |runtime| {
+            if !runtime.has("v1_visual_get_details_background_css", "visual.get_details_background_css")? {
+                return Ok(None);
+            }
+
+            runtime.call("v1_visual_get_details_background_css", "visual.get_details_background_css", edition)
+        })
+    }
+
+    #[inline]
+    #[cached::proc_macro::cached(
+        key = "String",
+        convert = r##"{ cache_key(_game, edition) }"##,
+        time = 600,
+        result
+    )]
+    pub fn get_news(driver: &Driver, _game: &str, edition: &str) -> anyhow::Result<Vec<News>> {
+        let edition = edition.to_string();
+
+        driver.execute(move |runtime| {
+            if !runtime.has("v1_visual_get_news", "visual.get_news")? {
+                return Ok(Vec::new());
+            }
+
+            let news = runtime.call::<_, LuaTable>("v1_visual_get_news", "visual.get_news", edition)?
+                .sequence_values::<LuaTable>()
+                .flatten()
+                .map(|news| News::from_table(news, runtime.standard))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(news)
+        })
+    }
+
+    #[inline]
+    #[cached::proc_macro::cached(
+        key = "String",
+        convert = r##"{ cache_key(_game, "") }"##,
+        time = 600,
+        result
+    )]
+    pub fn get_game_editions_list(driver: &Driver, _game: &str) -> anyhow::Result<Vec<GameEdition>> {
+        driver.execute(|runtime| {
+            let editions = runtime.call::<_, LuaTable>("v1_game_get_editions_list", "game.get_editions_list", ())?
+                .sequence_values::<LuaTable>()
+                .flatten()
+                .map(|edition| GameEdition::from_table(edition, runtime.standard))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(editions)
+        })
+    }
+
+    #[inline]
+    #[cached::proc_macro::cached(
+        key = "String",
+        convert = r##"{ cache_key(_game, edition) }"##,
+        time = 600,
+        result
+    )]
+    pub fn get_settings_schema(driver: &Driver, _game: &str, edition: &str) -> anyhow::Result<Vec<Setting>> {
+        let edition = edition.to_string();
+
+        driver.execute(move |runtime| {
+            if !runtime.has("v1_settings_get_schema", "settings.get_schema")? {
+                return Ok(Vec::new());
+            }
+
+            let schema = runtime.call::<_, LuaTable>("v1_settings_get_schema", "settings.get_schema", edition)?
+                .sequence_values::<LuaTable>()
+                .flatten()
+                .map(|setting| Setting::from_table(setting, runtime.standard))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(schema)
+        })
+    }
+
+    #[inline]
+    #[cached::proc_macro::cached(
+        key = "String",
+        convert = r##"{ cache_key(_game, edition) }"##,
+        time = 600,
+        result
+    )]
+    pub fn get_addons_list(driver: &Driver, _game: &str, edition: &str) -> anyhow::Result<Vec<AddonsGroup>> {
+        let edition = edition.to_string();
+
+        driver.execute(move |runtime| {
+            let addons = runtime.call::<_, LuaTable>("v1_addons_get_list", "addons.get_list", edition)?
+                .sequence_values::<LuaTable>()
+                .flatten()
+                .map(|group| AddonsGroup::from_table(group, runtime.standard))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(addons)
+        })
+    }
+
+    /// Remove all the cached results
+    pub fn clear() {
+        GET_CARD_PICTURE.lock().expect("Failed to lock cached results").cache_clear();
+        GET_BACKGROUND_PICTURE.lock().expect("Failed to lock cached results").cache_clear();
+        GET_DETAILS_BACKGROUND_STYLE.lock().expect("Failed to lock cached results").cache_clear();
+        GET_NEWS.lock().expect("Failed to lock cached results").cache_clear();
+        GET_GAME_EDITIONS_LIST.lock().expect("Failed to lock cached results").cache_clear();
+        GET_SETTINGS_SCHEMA.lock().expect("Failed to lock cached results").cache_clear();
+        GET_ADDONS_LIST.lock().expect("Failed to lock cached results").cache_clear();
     }
 }
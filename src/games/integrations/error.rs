@@ -0,0 +1,85 @@
+use mlua::prelude::*;
+
+use crate::tr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Error thrown by the integration API function
+pub struct IntegrationError {
+    pub game_name: String,
+    pub api: String,
+
+    /// Arguments the function was called with
+    pub args: Vec<String>,
+
+    pub message: String,
+
+    /// Lua stack traceback of the error
+    pub traceback: Option<String>
+}
+
+impl IntegrationError {
+    pub fn new(game_name: impl ToString, api: impl ToString, args: &LuaMultiValue, error: &LuaError) -> Self {
+        let (message, traceback) = split_error(error);
+
+        Self {
+            game_name: game_name.to_string(),
+            api: api.to_string(),
+            args: args.iter().map(format_value).collect(),
+            message,
+            traceback
+        }
+    }
+}
+
+impl std::fmt::Display for IntegrationError {
+    // First line is a short description of the error,
+    // and the rest are details for the script maintainers
+    // so they're not translated
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", tr!("integration-script-failed", {
+            "game-name" = self.game_name.as_str(),
+            "api" = self.api.as_str(),
+            "message" = self.message.as_str()
+        }))?;
+
+        writeln!(f)?;
+        writeln!(f, "Launcher version: {}", crate::APP_VERSION)?;
+        writeln!(f, "Integration: {}", self.game_name)?;
+        write!(f, "Call: {}({})", self.api, self.args.join(", "))?;
+
+        if let Some(traceback) = &self.traceback {
+            write!(f, "\n\n{traceback}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for IntegrationError {}
+
+/// Split lua error to its message and traceback
+fn split_error(error: &LuaError) -> (String, Option<String>) {
+    match error {
+        LuaError::CallbackError { traceback, cause } => (split_error(cause).0, Some(traceback.clone())),
+
+        // Errors thrown by the lua code contain traceback in the message
+        LuaError::RuntimeError(message) => match message.split_once("\nstack traceback:") {
+            Some((message, traceback)) => (message.to_string(), Some(format!("stack traceback:{traceback}"))),
+            None => (message.clone(), None)
+        },
+
+        _ => (error.to_string(), None)
+    }
+}
+
+fn format_value(value: &LuaValue) -> String {
+    match value {
+        LuaValue::Nil => String::from("nil"),
+        LuaValue::Boolean(value) => value.to_string(),
+        LuaValue::Integer(value) => value.to_string(),
+        LuaValue::Number(value) => value.to_string(),
+        LuaValue::String(value) => format!("{:?}", value.to_string_lossy()),
+
+        _ => value.type_name().to_string()
+    }
+}
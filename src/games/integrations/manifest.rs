@@ -1,6 +1,10 @@
+use std::cmp::Ordering;
+use std::path::{Path, Component};
+
 use serde_json::Value as Json;
 
 use super::standards::IntegrationStandard;
+use super::capabilities::Capability;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Manifest {
@@ -10,58 +14,220 @@ pub struct Manifest {
 
     pub script_path: String,
     pub script_version: String,
-    pub script_standard: IntegrationStandard
+    pub script_standard: IntegrationStandard,
+    pub script_capabilities: Vec<Capability>,
+
+    /// Additional script files (e.g. lua modules)
+    pub script_files: Vec<String>,
+
+    /// Minimal launcher version required by the integration
+    pub launcher_min_version: Option<String>,
+
+    /// Additional files used by the integration (e.g. pictures)
+    pub assets: Vec<String>,
+
+    pub changelog: Vec<ChangelogEntry>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChangelogEntry {
+    pub version: String,
+    pub changes: Vec<String>
 }
 
 impl Manifest {
     pub fn from_json(manifest: &Json) -> anyhow::Result<Self> {
         match manifest.get("manifest_version").and_then(Json::as_str) {
-            Some("1") => {
-                let Some(game_manifest) = manifest.get("game") else {
-                    anyhow::bail!("Wrong manifest v1 structure: field `game` expected but wasn't presented");
+            Some("1") => Self::parse_base(manifest, "1"),
+
+            Some("2") => {
+                let mut parsed = Self::parse_base(manifest, "2")?;
+
+                let script_manifest = &manifest["script"];
+
+                parsed.script_files = parse_paths(script_manifest.get("files"), "script.files")?;
+                parsed.assets = parse_paths(manifest.get("assets"), "assets")?;
+
+                parsed.launcher_min_version = match manifest.get("launcher") {
+                    Some(launcher) => launcher.get("min_version")
+                        .map(|version| version.as_str()
+                            .map(String::from)
+                            .ok_or_else(|| anyhow::anyhow!("Wrong manifest v2 structure: field `launcher.min_version` must be a string")))
+                        .transpose()?,
+
+                    None => None
                 };
 
-                let Some(script_manifest) = manifest.get("script") else {
-                    anyhow::bail!("Wrong manifest v1 structure: field `script` expected but wasn't presented");
+                parsed.changelog = match manifest.get("changelog") {
+                    Some(changelog) => changelog.as_array()
+                        .ok_or_else(|| anyhow::anyhow!("Wrong manifest v2 structure: field `changelog` must be an array"))?
+                        .iter()
+                        .map(ChangelogEntry::from_json)
+                        .collect::<Result<Vec<_>, _>>()?,
+
+                    None => vec![]
                 };
 
-                Ok(Self {
-                    game_name: game_manifest.get("name")
-                        .and_then(Json::as_str)
-                        .ok_or_else(|| anyhow::anyhow!("Wrong manifest v1 structure: field `game.name` expected but wasn't presented"))?
-                        .to_string(),
-
-                    game_title: game_manifest.get("title")
-                        .and_then(Json::as_str)
-                        .ok_or_else(|| anyhow::anyhow!("Wrong manifest v1 structure: field `game.title` expected but wasn't presented"))?
-                        .to_string(),
-
-                    game_developer: game_manifest.get("developer")
-                        .and_then(Json::as_str)
-                        .ok_or_else(|| anyhow::anyhow!("Wrong manifest v1 structure: field `game.developer` expected but wasn't presented"))?
-                        .to_string(),
-
-                    script_path: script_manifest.get("path")
-                        .and_then(Json::as_str)
-                        .ok_or_else(|| anyhow::anyhow!("Wrong manifest v1 structure: field `script.path` expected but wasn't presented"))?
-                        .to_string(),
-
-                    script_version: script_manifest.get("version")
-                        .and_then(Json::as_str)
-                        .ok_or_else(|| anyhow::anyhow!("Wrong manifest v1 structure: field `script.version` expected but wasn't presented"))?
-                        .to_string(),
-
-                    script_standard: match script_manifest.get("standard").and_then(Json::as_str) {
-                        Some("1") => IntegrationStandard::V1,
-
-                        Some(version) => anyhow::bail!("Wrong manifest v1 structure: field `script.standard` containts unknown version: {version}"),
-                        None => anyhow::bail!("Wrong manifest v1 structure: field `script.standard` expected but wasn't presented")
-                    }
-                })
+                Ok(parsed)
             }
 
             Some(version) => anyhow::bail!("Unknown manifest version: {version}"),
             None => anyhow::bail!("Wrong manifest file structure")
         }
     }
+
+    /// Parse fields shared by all the manifest versions
+    fn parse_base(manifest: &Json, version: &str) -> anyhow::Result<Self> {
+        let Some(game_manifest) = manifest.get("game") else {
+            anyhow::bail!("Wrong manifest v{version} structure: field `game` expected but wasn't presented");
+        };
+
+        let Some(script_manifest) = manifest.get("script") else {
+            anyhow::bail!("Wrong manifest v{version} structure: field `script` expected but wasn't presented");
+        };
+
+        let script_standard = match script_manifest.get("standard").and_then(Json::as_str) {
+            Some("1") => IntegrationStandard::V1,
+            Some("2") => IntegrationStandard::V2,
+
+            Some(standard) => anyhow::bail!("Wrong manifest v{version} structure: field `script.standard` containts unknown version: {standard}"),
+            None => anyhow::bail!("Wrong manifest v{version} structure: field `script.standard` expected but wasn't presented")
+        };
+
+        let script_path = script_manifest.get("path")
+            .and_then(Json::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Wrong manifest v{version} structure: field `script.path` expected but wasn't presented"))?;
+
+        if !is_relative_path(script_path) {
+            anyhow::bail!("Wrong manifest v{version} structure: field `script.path` contains path outside of the integration folder: {script_path}");
+        }
+
+        Ok(Self {
+            game_name: game_manifest.get("name")
+                .and_then(Json::as_str)
+                .ok_or_else(|| anyhow::anyhow!("Wrong manifest v{version} structure: field `game.name` expected but wasn't presented"))?
+                .to_string(),
+
+            game_title: game_manifest.get("title")
+                .and_then(Json::as_str)
+                .ok_or_else(|| anyhow::anyhow!("Wrong manifest v{version} structure: field `game.title` expected but wasn't presented"))?
+                .to_string(),
+
+            game_developer: game_manifest.get("developer")
+                .and_then(Json::as_str)
+                .ok_or_else(|| anyhow::anyhow!("Wrong manifest v{version} structure: field `game.developer` expected but wasn't presented"))?
+                .to_string(),
+
+            script_path: script_path.to_string(),
+
+            script_version: script_manifest.get("version")
+                .and_then(Json::as_str)
+                .ok_or_else(|| anyhow::anyhow!("Wrong manifest v{version} structure: field `script.version` expected but wasn't presented"))?
+                .to_string(),
+
+            script_standard,
+
+            script_capabilities: match script_manifest.get("capabilities") {
+                Some(capabilities) => capabilities.as_array()
+                    .ok_or_else(|| anyhow::anyhow!("Wrong manifest v{version} structure: field `script.capabilities` must be an array"))?
+                    .iter()
+                    .map(|capability| capability.as_str()
+                        .ok_or_else(|| anyhow::anyhow!("Wrong manifest v{version} structure: field `script.capabilities` must contain strings"))?
+                        .parse::<Capability>())
+                    .collect::<Result<Vec<_>, _>>()?,
+
+                // Keep v1 scripts made before the sandbox working
+                None if script_standard == IntegrationStandard::V1 => Capability::legacy(),
+                None => vec![]
+            },
+
+            script_files: vec![],
+            launcher_min_version: None,
+            assets: vec![],
+            changelog: vec![]
+        })
+    }
+
+    /// Check if the current launcher version satisfies the integration requirements
+    pub fn is_launcher_supported(&self) -> bool {
+        match &self.launcher_min_version {
+            Some(version) => compare_versions(crate::APP_VERSION, version) != Ordering::Less,
+            None => true
+        }
+    }
+
+    /// Get list of all the integration files except the manifest
+    pub fn files(&self) -> Vec<&str> {
+        let mut files = vec![self.script_path.as_str()];
+
+        files.extend(self.script_files.iter().map(String::as_str));
+        files.extend(self.assets.iter().map(String::as_str));
+
+        files
+    }
+}
+
+impl ChangelogEntry {
+    pub fn from_json(entry: &Json) -> anyhow::Result<Self> {
+        Ok(Self {
+            version: entry.get("version")
+                .and_then(Json::as_str)
+                .ok_or_else(|| anyhow::anyhow!("Wrong manifest v2 structure: field `changelog[].version` expected but wasn't presented"))?
+                .to_string(),
+
+            changes: entry.get("changes")
+                .and_then(Json::as_array)
+                .ok_or_else(|| anyhow::anyhow!("Wrong manifest v2 structure: field `changelog[].changes` expected but wasn't presented"))?
+                .iter()
+                .filter_map(|change| change.as_str().map(String::from))
+                .collect()
+        })
+    }
+}
+
+/// Parse list of relative file paths
+///
+/// Paths must stay inside of the integration folder
+fn parse_paths(paths: Option<&Json>, field: &str) -> anyhow::Result<Vec<String>> {
+    let Some(paths) = paths else {
+        return Ok(vec![]);
+    };
+
+    paths.as_array()
+        .ok_or_else(|| anyhow::anyhow!("Wrong manifest v2 structure: field `{field}` must be an array"))?
+        .iter()
+        .map(|path| {
+            let path = path.as_str()
+                .ok_or_else(|| anyhow::anyhow!("Wrong manifest v2 structure: field `{field}` must contain strings"))?;
+
+            if !is_relative_path(path) {
+                anyhow::bail!("Wrong manifest v2 structure: field `{field}` contains path outside of the integration folder: {path}");
+            }
+
+            Ok(path.to_string())
+        })
+        .collect()
+}
+
+/// Check that the path stays inside of the integration folder
+fn is_relative_path(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Compare dot-separated numeric versions (`1.0.2` < `1.1.0`)
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |version: &str| version.split('.')
+        .map(|part| part.trim().parse::<u64>().unwrap_or(0))
+        .collect::<Vec<_>>();
+
+    let mut a = parse(a);
+    let mut b = parse(b);
+
+    let len = a.len().max(b.len());
+
+    a.resize(len, 0);
+    b.resize(len, 0);
+
+    a.cmp(&b)
 }
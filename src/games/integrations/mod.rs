@@ -2,11 +2,23 @@ use std::path::{Path, PathBuf};
 
 use serde_json::Value as Json;
 
+use crate::config;
+
 pub mod manifest;
 pub mod standards;
+pub mod capabilities;
+pub mod sandbox;
+pub mod modules;
+pub mod budget;
+pub mod error;
+pub mod signature;
+pub mod builtins;
+pub mod runtime;
 pub mod driver;
+pub mod conformance;
 
 use manifest::Manifest;
+use capabilities::Capability;
 use driver::Driver;
 
 #[derive(Debug)]
@@ -16,26 +28,24 @@ pub struct Game {
 }
 
 impl Game {
+    #[inline]
+    /// Load integration script with capabilities approved by the user
     pub fn new(manifest_path: impl AsRef<Path>) -> anyhow::Result<Self> {
-        let manifest = std::fs::read(manifest_path.as_ref())?;
-        let manifest = serde_json::from_slice::<Json>(&manifest)?;
-        let manifest = Manifest::from_json(&manifest)?;
-
-        let script_path = PathBuf::from(&manifest.script_path);
+        Self::with_capabilities(manifest_path, |manifest| {
+            config::get().games.integrations.get_approved_capabilities(&manifest.game_name)
+        })
+    }
 
-        let script_path = if script_path.is_absolute() {
-            script_path
-        } else {
-            manifest_path.as_ref()
-                .parent()
-                .map(|path| path.join(&script_path))
-                .unwrap_or(script_path)
-        };
+    /// Load integration script with capabilities approved by the given function
+    pub fn with_capabilities(manifest_path: impl AsRef<Path>, approved: impl FnOnce(&Manifest) -> Vec<Capability>) -> anyhow::Result<Self> {
+        let (manifest, integration_path, script) = Self::read(manifest_path.as_ref(), approved)?;
 
         let driver = Driver::new(
             &manifest.game_name,
             manifest.script_standard,
-            std::fs::read_to_string(script_path)?
+            &manifest.script_capabilities,
+            &integration_path,
+            script
         )?;
 
         Ok(Self {
@@ -43,4 +53,58 @@ impl Game {
             driver
         })
     }
+
+    /// Reload integration script from the disk
+    /// 
+    /// Changes of the game info in the manifest are not applied
+    pub fn reload(&self, manifest_path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let (manifest, integration_path, script) = Self::read(manifest_path.as_ref(), |manifest| {
+            config::get().games.integrations.get_approved_capabilities(&manifest.game_name)
+        })?;
+
+        if manifest.game_name != self.manifest.game_name || manifest.script_standard != self.manifest.script_standard {
+            anyhow::bail!("Integration script {} changed its name or standard and must be loaded again", self.manifest.game_name);
+        }
+
+        self.driver.reload(&manifest.script_capabilities, integration_path, script)
+    }
+
+    /// Read and verify integration manifest, and read its script
+    fn read(manifest_path: &Path, approved: impl FnOnce(&Manifest) -> Vec<Capability>) -> anyhow::Result<(Manifest, PathBuf, String)> {
+        let manifest = std::fs::read(manifest_path)?;
+        let manifest = serde_json::from_slice::<Json>(&manifest)?;
+        let manifest = Manifest::from_json(&manifest)?;
+
+        if !manifest.is_launcher_supported() {
+            anyhow::bail!(
+                "Integration script {} requires launcher version {} or newer",
+                manifest.game_name,
+                manifest.launcher_min_version.as_deref().unwrap_or_default()
+            );
+        }
+
+        // Refuse to load scripts which require capabilities not approved by the user
+        let approved = approved(&manifest);
+
+        let unapproved = manifest.script_capabilities.iter()
+            .filter(|capability| !approved.contains(capability))
+            .map(|capability| capability.to_str())
+            .collect::<Vec<_>>();
+
+        if !unapproved.is_empty() {
+            anyhow::bail!("Integration script {} requires capabilities which weren't approved: {}", manifest.game_name, unapproved.join(", "));
+        }
+
+        let integration_path = manifest_path
+            .parent()
+            .map(PathBuf::from)
+            .unwrap_or_default();
+
+        // Manifest only allows paths relative to the integration folder
+        let script_path = integration_path.join(&manifest.script_path);
+
+        let script = std::fs::read_to_string(script_path)?;
+
+        Ok((manifest, integration_path, script))
+    }
 }
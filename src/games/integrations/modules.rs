@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use mlua::prelude::*;
+use mlua::ChunkMode;
+
+/// Resolve module name (`common.hoyo`) to the lua file inside of the given root folder
+///
+/// Return `None` if the module file doesn't exist or is located outside of the root
+fn resolve(root: &Path, name: &str) -> Option<PathBuf> {
+    let root = std::fs::canonicalize(root).ok()?;
+
+    let relative = name.split('.').collect::<PathBuf>();
+
+    let candidates = [
+        root.join(&relative).with_extension("lua"),
+        root.join(&relative).join("init.lua")
+    ];
+
+    candidates.into_iter()
+        .filter_map(|path| std::fs::canonicalize(path).ok())
+        .find(|path| path.is_file() && path.starts_with(&root))
+}
+
+/// Check that the module name consists of simple dot-separated segments
+fn is_valid_name(name: &str) -> bool {
+    name.split('.').all(|segment| {
+        !segment.is_empty() && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    })
+}
+
+/// Register `require` function which loads lua modules
+/// from the integration folder or the shared modules folder
+///
+/// Precompiled bytecode and native modules are not supported
+pub fn register(lua: &Lua, integration_path: impl Into<PathBuf>, shared_path: impl Into<PathBuf>) -> LuaResult<()> {
+    let integration_path = integration_path.into();
+    let shared_path = shared_path.into();
+
+    let loaded = lua.create_registry_value(lua.create_table()?)?;
+
+    lua.globals().set("require", lua.create_function(move |lua, name: String| {
+        let loaded = lua.registry_value::<LuaTable>(&loaded)?;
+
+        if let Some(module) = loaded.get::<_, Option<LuaValue>>(name.as_str())? {
+            return Ok(module);
+        }
+
+        if !is_valid_name(&name) {
+            return Err(LuaError::RuntimeError(format!("Invalid module name: '{name}'")));
+        }
+
+        let Some(path) = resolve(&integration_path, &name).or_else(|| resolve(&shared_path, &name)) else {
+            return Err(LuaError::RuntimeError(format!("Module '{name}' not found")));
+        };
+
+        let source = std::fs::read(&path)
+            .map_err(LuaError::external)?;
+
+        let module = lua.load(source)
+            .set_name(format!("@{}", path.to_string_lossy()))
+            .set_mode(ChunkMode::Text)
+            .call::<_, LuaValue>(name.as_str())?;
+
+        // Modules which don't return anything are stored as `true`, like in the standard `require`
+        let module = match module {
+            LuaValue::Nil => LuaValue::Boolean(true),
+            module => module
+        };
+
+        loaded.set(name.as_str(), module.clone())?;
+
+        Ok(module)
+    })?)?;
+
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use std::path::Path;
+
+use mlua::prelude::*;
+
+use crate::config;
+use crate::config::games::integrations::timeouts::Timeouts;
+
+use super::standards::prelude::*;
+use super::capabilities::{Capability, FilesystemScope};
+use super::sandbox;
+use super::modules;
+use super::budget::{Budget, ApiCategory, TimeoutError};
+use super::error::IntegrationError;
+use super::builtins;
+use super::builtins::Progress;
+use super::builtins::fs::FilesystemRoots;
+
+#[derive(Debug)]
+/// Lua state of the integration script
+/// 
+/// Lives on the integration's own thread and receives
+/// requests from the `Driver` proxy
+pub struct Runtime {
+    pub game_name: String,
+    pub standard: IntegrationStandard,
+    pub lua: Lua,
+
+    /// Module table returned by the V2 standard script
+    module: Option<LuaRegistryKey>,
+
+    /// Folders accessible by the filesystem built-ins
+    roots: FilesystemRoots,
+
+    /// Progress reporter of the long running built-ins
+    pub progress: Progress,
+
+    /// Execution budget of the API calls
+    budget: Budget,
+    timeouts: Timeouts
+}
+
+impl Runtime {
+    pub fn new(
+        game_name: impl ToString,
+        standard: IntegrationStandard,
+        capabilities: &[Capability],
+        integration_path: impl AsRef<Path>,
+        script: impl AsRef<str>,
+        progress: Progress
+    ) -> anyhow::Result<Self> {
+        let lua = sandbox::create(capabilities)?;
+        let budget = Budget::install(&lua);
+        let roots = FilesystemRoots::new(capabilities);
+
+        roots.set(FilesystemScope::Integration, integration_path.as_ref());
+
+        let game_name = game_name.to_string();
+
+        builtins::register(&lua, &game_name, standard, capabilities, &roots, &progress, &budget)?;
+
+        let config = config::get();
+
+        modules::register(&lua, integration_path.as_ref(), config.games.integrations.shared_path)?;
+
+        // Top-level code can hang the loading window as well
+        let timeout = config.games.integrations.timeouts.get(ApiCategory::Load);
+
+        budget.start(timeout);
+
+        let module = match standard {
+            IntegrationStandard::V1 => lua.load(script.as_ref())
+                .exec()
+                .map(|_| None),
+
+            IntegrationStandard::V2 => lua.load(script.as_ref())
+                .eval::<LuaTable>()
+                .map(Some)
+        };
+
+        if budget.finish() {
+            return Err(TimeoutError {
+                game_name,
+                api: String::from("script loading"),
+                timeout: timeout.unwrap_or_default()
+            }.into());
+        }
+
+        let module = match module? {
+            Some(module) => {
+                Self::negotiate(&lua, &module)?;
+
+                Some(lua.create_registry_value(module)?)
+            }
+
+            None => None
+        };
+
+        Ok(Self {
+            game_name,
+            standard,
+            lua,
+            module,
+            roots,
+            progress,
+            budget,
+            timeouts: config.games.integrations.timeouts
+        })
+    }
+
+    /// Verify that the launcher provides all the built-ins
+    /// listed in the `requires` field of the V2 module table
+    fn negotiate(lua: &Lua, module: &LuaTable) -> anyhow::Result<()> {
+        let Some(requires) = module.get::<_, Option<LuaTable>>("requires")? else {
+            return Ok(());
+        };
+
+        let features = lua.globals()
+            .get::<_, LuaTable>("launcher")?
+            .get::<_, LuaTable>("features")?;
+
+        let missing = requires.sequence_values::<String>()
+            .filter_map(|feature| match feature {
+                Ok(feature) => match features.contains_key(feature.as_str()) {
+                    Ok(true) => None,
+                    Ok(false) => Some(Ok(feature)),
+                    Err(err) => Some(Err(err))
+                },
+
+                Err(err) => Some(Err(err))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !missing.is_empty() {
+            anyhow::bail!("Integration script requires unavailable launcher features: {}", missing.join(", "));
+        }
+
+        Ok(())
+    }
+
+    /// Find integration API function
+    /// 
+    /// V1 standard functions are looked up by their global name,
+    /// V2 - by their dot-separated path in the module table
+    pub fn get_function(&self, v1_name: &str, v2_path: &str) -> anyhow::Result<Option<LuaFunction>> {
+        match self.standard {
+            IntegrationStandard::V1 => Ok(self.lua.globals().get(v1_name)?),
+
+            IntegrationStandard::V2 => {
+                let Some(module) = &self.module else {
+                    anyhow::bail!("V2 integration script didn't return module table");
+                };
+
+                let mut value = LuaValue::Table(self.lua.registry_value::<LuaTable>(module)?);
+
+                for name in v2_path.split('.') {
+                    value = match value {
+                        LuaValue::Table(table) => table.get(name)?,
+
+                        _ => return Ok(None)
+                    };
+                }
+
+                match value {
+                    LuaValue::Function(function) => Ok(Some(function)),
+
+                    _ => Ok(None)
+                }
+            }
+        }
+    }
+
+    #[inline]
+    /// Check if the integration script implements given API function
+    pub fn has(&self, v1_name: &str, v2_path: &str) -> anyhow::Result<bool> {
+        Ok(self.get_function(v1_name, v2_path)?.is_some())
+    }
+
+    /// Call integration API function
+    pub fn call<'lua, A, R>(&'lua self, v1_name: &str, v2_path: &str, args: A) -> anyhow::Result<R>
+    where
+        A: IntoLuaMulti<'lua>,
+        R: FromLuaMulti<'lua>
+    {
+        let Some(function) = self.get_function(v1_name, v2_path)? else {
+            match self.standard {
+                IntegrationStandard::V1 => anyhow::bail!("Integration script doesn't implement {v1_name} function"),
+                IntegrationStandard::V2 => anyhow::bail!("Integration script doesn't implement {v2_path} function")
+            }
+        };
+
+        let api = match self.standard {
+            IntegrationStandard::V1 => v1_name,
+            IntegrationStandard::V2 => v2_path
+        };
+
+        let args = args.into_lua_multi(&self.lua)?;
+
+        let timeout = self.timeouts.get(ApiCategory::from_path(v2_path));
+
+        self.budget.start(timeout);
+
+        let result = function.call(args.clone());
+
+        if self.budget.finish() {
+            return Err(TimeoutError {
+                game_name: self.game_name.clone(),
+                api: api.to_string(),
+                timeout: timeout.unwrap_or_default()
+            }.into());
+        }
+
+        result.map_err(|err| IntegrationError::new(&self.game_name, api, &args, &err).into())
+    }
+
+    /// Call integration API function making given folders
+    /// accessible by the filesystem built-ins during the call
+    pub fn call_scoped<'lua, A, R>(&'lua self, scopes: &[(FilesystemScope, &str)], v1_name: &str, v2_path: &str, args: A) -> anyhow::Result<R>
+    where
+        A: IntoLuaMulti<'lua>,
+        R: FromLuaMulti<'lua>
+    {
+        for (scope, path) in scopes {
+            self.roots.set(*scope, path);
+        }
+
+        let result = self.call(v1_name, v2_path, args);
+
+        for (scope, _) in scopes {
+            self.roots.remove(*scope);
+        }
+
+        result
+    }
+}
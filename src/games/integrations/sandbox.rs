@@ -0,0 +1,109 @@
+use mlua::prelude::*;
+
+use super::capabilities::{
+    Capability,
+    FilesystemScope
+};
+
+/// `os` library functions available to every integration script
+const OS_SAFE_FUNCTIONS: &[&str] = &["clock", "date", "difftime", "getenv", "time"];
+
+/// `os` library functions which require `filesystem:system` capability
+const OS_FILESYSTEM_FUNCTIONS: &[&str] = &["remove", "rename", "tmpname"];
+
+/// `os` library functions which require `process` capability
+const OS_PROCESS_FUNCTIONS: &[&str] = &["execute"];
+
+/// Base library functions which read lua files from the disk
+const BASE_FILESYSTEM_FUNCTIONS: &[&str] = &["dofile", "loadfile"];
+
+/// Create new lua state with the standard library
+/// limited to the given capabilities
+pub fn create(capabilities: &[Capability]) -> LuaResult<Lua> {
+    let has_filesystem = capabilities.contains(&Capability::Filesystem(FilesystemScope::System));
+    let has_process = capabilities.contains(&Capability::Process);
+
+    let mut libs = StdLib::COROUTINE | StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::BIT | StdLib::OS;
+
+    if has_filesystem || has_process {
+        libs |= StdLib::IO;
+    }
+
+    // `new_with` refuses to load `debug` and `ffi` libraries,
+    // and `package` is not loaded at all so `package.loadlib` is unavailable
+    let lua = Lua::new_with(libs | StdLib::JIT, LuaOptions::default())?;
+
+    // Hooks are not called from the JIT-compiled code
+    // so the compiler is disabled to make execution budgets work
+    lua.load("jit.off(); jit.flush(); jit = nil").exec()?;
+
+    restrict(&lua, has_filesystem, has_process)?;
+
+    Ok(lua)
+}
+
+fn restrict(lua: &Lua, has_filesystem: bool, has_process: bool) -> LuaResult<()> {
+    let globals = lua.globals();
+
+    if !has_filesystem {
+        for name in BASE_FILESYSTEM_FUNCTIONS {
+            globals.raw_remove(*name)?;
+        }
+    }
+
+    // Limit `os` library
+
+    let os = globals.get::<_, LuaTable>("os")?;
+
+    let os_functions = os.clone()
+        .pairs::<String, LuaValue>()
+        .map(|pair| pair.map(|(name, _)| name))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for name in os_functions {
+        let allowed = OS_SAFE_FUNCTIONS.contains(&name.as_str()) ||
+            (has_filesystem && OS_FILESYSTEM_FUNCTIONS.contains(&name.as_str())) ||
+            (has_process && OS_PROCESS_FUNCTIONS.contains(&name.as_str()));
+
+        if !allowed {
+            os.raw_remove(name)?;
+        }
+    }
+
+    // Limit `io` library
+
+    if let Ok(io) = globals.get::<_, LuaTable>("io") {
+        if !has_filesystem {
+            let io_functions = io.clone()
+                .pairs::<String, LuaValue>()
+                .map(|pair| pair.map(|(name, _)| name))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for name in io_functions {
+                if name != "popen" {
+                    io.raw_remove(name)?;
+                }
+            }
+        }
+
+        else if !has_process {
+            io.raw_remove("popen")?;
+        }
+    }
+
+    // Forbid loading of precompiled bytecode which can be used
+    // to escape the sandbox
+    lua.load(r#"
+        local lua_load = load
+
+        function load(chunk, chunkname, _, env)
+            if env ~= nil then
+                return lua_load(chunk, chunkname, "t", env)
+            end
+
+            return lua_load(chunk, chunkname, "t")
+        end
+
+        loadstring = load
+    "#).exec()
+}
@@ -0,0 +1,54 @@
+use minisign_verify::{
+    PublicKey,
+    Signature,
+    Error as MinisignError
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureStatus {
+    /// File is signed by one of the trusted keys
+    Verified,
+
+    /// File has no signature, or there are no trusted keys to verify it
+    Unsigned
+}
+
+/// Verify detached minisign signature of the file
+///
+/// Return error if the signature is invalid, made by an untrusted key
+/// or doesn't match the file content. Signed files are reported
+/// as unsigned if there are no trusted keys since they can't be verified
+pub fn verify(data: &[u8], signature: Option<&[u8]>, trusted_keys: &[String]) -> anyhow::Result<SignatureStatus> {
+    let Some(signature) = signature else {
+        return Ok(SignatureStatus::Unsigned);
+    };
+
+    if trusted_keys.is_empty() {
+        return Ok(SignatureStatus::Unsigned);
+    }
+
+    let signature = Signature::decode(std::str::from_utf8(signature)?)
+        .map_err(|err| anyhow::anyhow!("Failed to decode signature: {err}"))?;
+
+    for key in trusted_keys {
+        let key = match PublicKey::from_base64(key) {
+            Ok(key) => key,
+            Err(err) => {
+                tracing::warn!("Failed to decode trusted public key {key}: {err}");
+
+                continue;
+            }
+        };
+
+        match key.verify(data, &signature, false) {
+            Ok(()) => return Ok(SignatureStatus::Verified),
+
+            // Signature was made by another key
+            Err(MinisignError::UnexpectedKeyId) => continue,
+
+            Err(err) => anyhow::bail!("File content doesn't match its signature: {err}")
+        }
+    }
+
+    anyhow::bail!("Signature was made by an untrusted key")
+}
@@ -14,7 +14,7 @@ pub struct Diff {
 impl Diff {
     pub fn from_table(table: LuaTable, standard: IntegrationStandard) -> anyhow::Result<Self> {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 Ok(Self {
                     current_version: table.get::<_, String>("current_version")?,
                     latest_version: table.get::<_, String>("latest_version")?,
@@ -34,7 +34,7 @@ impl Diff {
 
     pub fn to_table<'a>(&self, lua: &'a Lua, standard: IntegrationStandard) -> anyhow::Result<LuaTable<'a>> {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 let table = lua.create_table()?;
 
                 table.set("current_version", self.current_version.as_str())?;
@@ -62,7 +62,7 @@ pub enum DiffStatus {
 impl DiffStatus {
     pub fn from_str(value: impl AsRef<str>, standard: IntegrationStandard) -> anyhow::Result<Self> {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 match value.as_ref() {
                     "latest"      => Ok(Self::Latest),
                     "outdated"    => Ok(Self::Outdated),
@@ -76,7 +76,7 @@ impl DiffStatus {
 
     pub fn to_str(&self, standard: IntegrationStandard) -> &str {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 match self {
                     Self::Latest      => "latest",
                     Self::Outdated    => "outdated",
@@ -106,7 +106,7 @@ pub enum DiffInfo {
 impl DiffInfo {
     pub fn from_table(table: LuaTable, standard: IntegrationStandard) -> anyhow::Result<Self> {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 let size = table.get::<_, u64>("size")?;
 
                 match table.get::<_, String>("type")?.as_str() {
@@ -140,7 +140,7 @@ impl DiffInfo {
 
     pub fn to_table<'a>(&self, lua: &'a Lua, standard: IntegrationStandard) -> anyhow::Result<LuaTable<'a>> {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 let table = lua.create_table()?;
 
                 match self {
@@ -191,7 +191,7 @@ pub struct DiffFileDownload {
 impl DiffFileDownload {
     pub fn from_table(table: LuaTable, standard: IntegrationStandard) -> anyhow::Result<Self> {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 Ok(Self {
                     path: table.get::<_, String>("path")?,
                     uri: table.get::<_, String>("uri")?,
@@ -203,7 +203,7 @@ impl DiffFileDownload {
 
     pub fn to_table<'a>(&self, lua: &'a Lua, standard: IntegrationStandard) -> anyhow::Result<LuaTable<'a>> {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 let table = lua.create_table()?;
 
                 table.set("path", self.path.as_str())?;
@@ -1,3 +1,6 @@
+use std::io::Read;
+use std::path::Path;
+
 use mlua::prelude::*;
 
 use super::IntegrationStandard;
@@ -13,7 +16,7 @@ pub struct IntegrityInfo {
 impl IntegrityInfo {
     pub fn from_table(table: LuaTable, standard: IntegrationStandard) -> anyhow::Result<Self> {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 Ok(Self {
                     hash: HashType::from_str(table.get::<_, String>("hash")?, standard)?,
                     value: table.get::<_, String>("value")?,
@@ -25,7 +28,7 @@ impl IntegrityInfo {
 
     pub fn to_table<'a>(&self, lua: &'a Lua, standard: IntegrationStandard) -> anyhow::Result<LuaTable<'a>> {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 let table = lua.create_table()?;
 
                 table.set("hash", self.hash.to_str(standard))?;
@@ -58,7 +61,7 @@ pub enum HashType {
 impl HashType {
     pub fn from_str(value: impl AsRef<str>, standard: IntegrationStandard) -> anyhow::Result<Self> {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 match value.as_ref() {
                     "md5"         => Ok(Self::Md5),
                     "sha1"        => Ok(Self::Sha1),
@@ -76,7 +79,7 @@ impl HashType {
 
     pub fn to_str(&self, standard: IntegrationStandard) -> &str {
         match standard {
-            IntegrationStandard::V1 => {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
                 match self {
                     Self::Md5         => "md5",
                     Self::Sha1        => "sha1",
@@ -92,3 +95,107 @@ impl HashType {
         }
     }
 }
+
+impl HashType {
+    /// Calculate hash of the given data using native implementation
+    /// 
+    /// Return `None` for custom hash types
+    pub fn hash(&self, data: impl AsRef<[u8]>) -> Option<String> {
+        // Reading from a slice can't fail
+        self.hash_reader(data.as_ref()).ok().flatten()
+    }
+
+    #[inline]
+    /// Calculate hash of the given file streaming its content
+    /// 
+    /// Return `None` for custom hash types
+    pub fn hash_file(&self, path: impl AsRef<Path>) -> std::io::Result<Option<String>> {
+        self.hash_reader(std::fs::File::open(path)?)
+    }
+
+    /// Calculate hash of the data from the given reader
+    /// 
+    /// Return `None` for custom hash types
+    pub fn hash_reader(&self, reader: impl Read) -> std::io::Result<Option<String>> {
+        let hash = match self {
+            Self::Md5 => {
+                use md5::{Md5, Digest};
+
+                let mut hasher = Md5::new();
+
+                stream(reader, |data| hasher.update(data))?;
+
+                format!("{:x}", hasher.finalize())
+            }
+
+            Self::Sha1 => {
+                use sha1::{Sha1, Digest};
+
+                let mut hasher = Sha1::new();
+
+                stream(reader, |data| hasher.update(data))?;
+
+                format!("{:x}", hasher.finalize())
+            }
+
+            Self::Crc32 => {
+                let mut hasher = crc32fast::Hasher::new();
+
+                stream(reader, |data| hasher.update(data))?;
+
+                hasher.finalize().to_string()
+            }
+
+            Self::Xxhash32 => {
+                let mut hasher = xxhash_rust::xxh32::Xxh32::new(0);
+
+                stream(reader, |data| hasher.update(data))?;
+
+                format!("{:x}", hasher.digest())
+            }
+
+            Self::Xxhash64 => {
+                let mut hasher = xxhash_rust::xxh64::Xxh64::new(0);
+
+                stream(reader, |data| hasher.update(data))?;
+
+                format!("{:x}", hasher.digest())
+            }
+
+            Self::Xxhash3_64 => {
+                let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+
+                stream(reader, |data| hasher.update(data))?;
+
+                format!("{:x}", hasher.digest())
+            }
+
+            Self::Xxhash3_128 => {
+                let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+
+                stream(reader, |data| hasher.update(data))?;
+
+                format!("{:x}", hasher.digest128())
+            }
+
+            Self::Custom(_) => return Ok(None)
+        };
+
+        Ok(Some(hash))
+    }
+}
+
+/// Pass reader's data to the callback by chunks
+fn stream(mut reader: impl Read, mut callback: impl FnMut(&[u8])) -> std::io::Result<()> {
+    let mut buf = [0; 64 * 1024];
+
+    loop {
+        let read = reader.read(&mut buf)?;
+
+        if read == 0 {
+            return Ok(());
+        }
+
+        callback(&buf[..read]);
+    }
+}
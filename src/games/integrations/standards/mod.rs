@@ -4,6 +4,8 @@ pub mod diff;
 pub mod download;
 pub mod integrity;
 pub mod network;
+pub mod settings;
+pub mod news;
 
 pub mod prelude {
     pub use super::game::{
@@ -17,11 +19,18 @@ pub mod prelude {
     pub use super::download::*;
     pub use super::integrity::*;
     pub use super::network::*;
+    pub use super::settings::*;
+    pub use super::news::*;
 
     pub use super::IntegrationStandard;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum IntegrationStandard {
-    V1
+    /// Script defines `v1_*` global functions
+    V1,
+
+    /// Script returns a module table with `game`, `addons`, `visual`
+    /// and `hooks` tables, and uses versioned `launcher` table built-ins
+    V2
 }
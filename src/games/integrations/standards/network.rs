@@ -16,7 +16,13 @@ pub struct RequestOptions {
     pub body: Option<Vec<u8>>,
 
     /// Request timeout, in seconds
-    pub timeout: Option<u64>
+    pub timeout: Option<u64>,
+
+    /// Ignore cached response even if it's still fresh
+    pub refresh: bool,
+
+    /// Return cached response if the server is not reachable
+    pub allow_stale: bool
 }
 
 impl<'lua> FromLua<'lua> for RequestOptions {
@@ -77,7 +83,13 @@ impl<'lua> FromLua<'lua> for RequestOptions {
 
             timeout: table.contains_key("timeout")?
                 .then(|| table.get("timeout"))
-                .transpose()?
+                .transpose()?,
+
+            refresh: table.get::<_, Option<bool>>("refresh")?
+                .unwrap_or_default(),
+
+            allow_stale: table.get::<_, Option<bool>>("allow_stale")?
+                .unwrap_or_default()
         })
     }
 }
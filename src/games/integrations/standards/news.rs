@@ -0,0 +1,38 @@
+use serde::{Serialize, Deserialize};
+
+use mlua::prelude::*;
+
+use super::IntegrationStandard;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Game news item, e.g. patch notes or maintenance notice
+pub struct News {
+    pub title: String,
+
+    /// Publication date as given by the integration script
+    pub date: Option<String>,
+
+    pub summary: Option<String>,
+
+    /// Link to the full article
+    pub uri: Option<String>,
+
+    /// URI or path of the news picture
+    pub image: Option<String>
+}
+
+impl News {
+    pub fn from_table(table: LuaTable, standard: IntegrationStandard) -> anyhow::Result<Self> {
+        match standard {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
+                Ok(Self {
+                    title: table.get::<_, String>("title")?,
+                    date: table.get::<_, Option<String>>("date")?,
+                    summary: table.get::<_, Option<String>>("summary")?,
+                    uri: table.get::<_, Option<String>>("uri")?,
+                    image: table.get::<_, Option<String>>("image")?
+                })
+            }
+        }
+    }
+}
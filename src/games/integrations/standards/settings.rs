@@ -0,0 +1,144 @@
+use serde_json::Value as Json;
+
+use mlua::prelude::*;
+
+use super::IntegrationStandard;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Game option declared by the integration script
+pub struct Setting {
+    pub name: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub kind: SettingKind,
+
+    /// Value used when the user didn't change the option
+    pub default: Json
+}
+
+impl Setting {
+    pub fn from_table(table: LuaTable, standard: IntegrationStandard) -> anyhow::Result<Self> {
+        match standard {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
+                let kind = SettingKind::from_table(&table, standard)?;
+
+                let default = match &kind {
+                    SettingKind::Bool => Json::from(table.get::<_, Option<bool>>("default")?.unwrap_or_default()),
+                    SettingKind::Number { min, .. } => Json::from(table.get::<_, Option<f64>>("default")?.or(*min).unwrap_or_default()),
+                    SettingKind::String | SettingKind::Path { .. } => Json::from(table.get::<_, Option<String>>("default")?.unwrap_or_default()),
+
+                    SettingKind::Enum(values) => match table.get::<_, Option<String>>("default")? {
+                        Some(default) => Json::from(default),
+                        None => values.first()
+                            .map(|value| Json::from(value.value.as_str()))
+                            .unwrap_or_default()
+                    }
+                };
+
+                let name = table.get::<_, String>("name")?;
+
+                // Values are stored by the dot-separated config path
+                if name.is_empty() || name.contains('.') {
+                    anyhow::bail!("Wrong v1 setting name: '{name}'");
+                }
+
+                Ok(Self {
+                    name,
+                    title: table.get::<_, String>("title")?,
+                    description: table.get::<_, Option<String>>("description")?,
+                    kind,
+                    default
+                })
+            }
+        }
+    }
+
+    /// Check if the value can be used for this setting
+    pub fn accepts(&self, value: &Json) -> bool {
+        match &self.kind {
+            SettingKind::Bool => value.is_boolean(),
+            SettingKind::String | SettingKind::Path { .. } => value.is_string(),
+
+            SettingKind::Number { min, max, .. } => match value.as_f64() {
+                Some(value) => min.map(|min| value >= min).unwrap_or(true) && max.map(|max| value <= max).unwrap_or(true),
+                None => false
+            },
+
+            SettingKind::Enum(values) => match value.as_str() {
+                Some(value) => values.iter().any(|variant| variant.value == value),
+                None => false
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingKind {
+    Bool,
+    String,
+
+    Number {
+        min: Option<f64>,
+        max: Option<f64>,
+        step: Option<f64>
+    },
+
+    Enum(Vec<SettingEnumValue>),
+
+    Path {
+        /// Select folder instead of a file
+        folder: bool
+    }
+}
+
+impl SettingKind {
+    pub fn from_table(table: &LuaTable, standard: IntegrationStandard) -> anyhow::Result<Self> {
+        match standard {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
+                match table.get::<_, String>("type")?.as_str() {
+                    "bool"   => Ok(Self::Bool),
+                    "string" => Ok(Self::String),
+
+                    "number" => Ok(Self::Number {
+                        min: table.get::<_, Option<f64>>("min")?,
+                        max: table.get::<_, Option<f64>>("max")?,
+                        step: table.get::<_, Option<f64>>("step")?
+                    }),
+
+                    "enum" => Ok(Self::Enum({
+                        table.get::<_, LuaTable>("values")?
+                            .sequence_values::<LuaTable>()
+                            .flatten()
+                            .map(|value| SettingEnumValue::from_table(value, standard))
+                            .collect::<Result<Vec<_>, _>>()?
+                    })),
+
+                    "path" => Ok(Self::Path {
+                        folder: table.get::<_, Option<bool>>("folder")?.unwrap_or_default()
+                    }),
+
+                    kind => anyhow::bail!("Wrong v1 setting type: '{kind}'")
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingEnumValue {
+    pub value: String,
+    pub title: String
+}
+
+impl SettingEnumValue {
+    pub fn from_table(table: LuaTable, standard: IntegrationStandard) -> anyhow::Result<Self> {
+        match standard {
+            IntegrationStandard::V1 | IntegrationStandard::V2 => {
+                Ok(Self {
+                    value: table.get::<_, String>("value")?,
+                    title: table.get::<_, String>("title")?
+                })
+            }
+        }
+    }
+}
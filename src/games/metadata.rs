@@ -7,14 +7,16 @@ use crate::LAUNCHER_FOLDER;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct LauncherMetadata {
-    pub launches: GameLaunchesMetadata
+    pub launches: GameLaunchesMetadata,
+    pub predownload: Option<GamePredownloadMetadata>
 }
 
 impl Default for LauncherMetadata {
     #[inline]
     fn default() -> Self {
         Self {
-            launches: GameLaunchesMetadata::default()
+            launches: GameLaunchesMetadata::default(),
+            predownload: None
         }
     }
 }
@@ -26,7 +28,17 @@ impl From<&Json> for LauncherMetadata {
         Self {
             launches: value.get("launches")
                 .map(GameLaunchesMetadata::from)
-                .unwrap_or(default.launches)
+                .unwrap_or(default.launches),
+
+            predownload: value.get("predownload")
+                .and_then(|value| {
+                    if value.is_null() {
+                        None
+                    } else {
+                        Some(GamePredownloadMetadata::from(value))
+                    }
+                })
+                .or(default.predownload)
         }
     }
 }
@@ -192,3 +204,31 @@ impl From<&Json> for GameLastLaunchMetadata {
         }
     }
 }
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Game update downloaded before its release
+pub struct GamePredownloadMetadata {
+    /// Version of the pre-downloaded update
+    pub version: String,
+
+    /// Name of the transition with staged update files
+    pub transition: String
+}
+
+impl From<&Json> for GamePredownloadMetadata {
+    fn from(value: &Json) -> Self {
+        let default = Self::default();
+
+        Self {
+            version: value.get("version")
+                .and_then(Json::as_str)
+                .map(String::from)
+                .unwrap_or(default.version),
+
+            transition: value.get("transition")
+                .and_then(Json::as_str)
+                .map(String::from)
+                .unwrap_or(default.transition)
+        }
+    }
+}
@@ -4,6 +4,9 @@ use crate::config;
 
 pub mod integrations;
 pub mod metadata;
+pub mod storage;
+pub mod news;
+pub mod hot_reload;
 
 static mut GAMES_SINGLETON: Option<HashMap<String, integrations::Game>> = None;
 
@@ -13,10 +16,17 @@ pub fn init() -> anyhow::Result<()> {
     let mut games = HashMap::new();
 
     for entry in integration_scripts.read_dir()?.flatten() {
-        if entry.path().is_dir() {
-            let game = integrations::Game::new(entry.path().join("manifest.json"))?;
-
-            games.insert(entry.file_name().to_string_lossy().to_string(), game);
+        // Hidden folders (e.g. quarantined updates) don't contain integrations
+        if entry.path().is_dir() && !entry.file_name().to_string_lossy().starts_with('.') {
+            // Skip integrations which can't be loaded (e.g. require unapproved capabilities)
+            // instead of breaking all the other games
+            match integrations::Game::new(entry.path().join("manifest.json")) {
+                Ok(game) => {
+                    games.insert(entry.file_name().to_string_lossy().to_string(), game);
+                }
+
+                Err(err) => tracing::error!("Failed to load {:?} integration: {err}", entry.file_name())
+            }
         }
     }
 
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use crate::LAUNCHER_FOLDER;
+
+use crate::games::integrations::standards::news::News;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Last news received from the integration script
+///
+/// Stored per game edition so the details page
+/// can show them without waiting for the script
+pub struct NewsCache {
+    pub news: Vec<News>
+}
+
+impl NewsCache {
+    #[inline]
+    fn path(game: impl AsRef<str>, edition: impl AsRef<str>) -> PathBuf {
+        LAUNCHER_FOLDER
+            .join("games")
+            .join(game.as_ref())
+            .join(edition.as_ref())
+            .join("news.json")
+    }
+
+    pub fn load_for_game(game: impl AsRef<str>, edition: impl AsRef<str>) -> anyhow::Result<Self> {
+        let path = Self::path(game, edition);
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        Ok(Self {
+            news: serde_json::from_slice(&std::fs::read(&path)?)?
+        })
+    }
+
+    pub fn save_for_game(&self, game: impl AsRef<str>, edition: impl AsRef<str>) -> anyhow::Result<()> {
+        let path = Self::path(game, edition);
+
+        if let Some(folder_path) = path.parent() {
+            if !folder_path.exists() {
+                std::fs::create_dir_all(folder_path)?;
+            }
+        }
+
+        std::fs::write(path, serde_json::to_vec(&self.news)?)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf, Component};
+
+use serde_json::{Value as Json, Map};
+
+use crate::LAUNCHER_FOLDER;
+
+/// Maximal size of the serialized storage, in bytes
+pub const MAX_STORAGE_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Persistent key-value storage of the integration script
+///
+/// Values are stored per game edition next to its launcher metadata
+pub struct IntegrationStorage {
+    pub values: Map<String, Json>
+}
+
+/// Check that the edition name can be used as a folder name
+pub fn is_valid_edition(edition: &str) -> bool {
+    let mut components = Path::new(edition).components();
+
+    matches!((components.next(), components.next()), (Some(Component::Normal(_)), None))
+}
+
+impl IntegrationStorage {
+    fn path(game: impl AsRef<str>, edition: impl AsRef<str>) -> anyhow::Result<PathBuf> {
+        if !is_valid_edition(edition.as_ref()) {
+            anyhow::bail!("Invalid game edition name: {}", edition.as_ref());
+        }
+
+        Ok(LAUNCHER_FOLDER
+            .join("games")
+            .join(game.as_ref())
+            .join(edition.as_ref())
+            .join("integration_storage.json"))
+    }
+
+    pub fn load_for_game(game: impl AsRef<str>, edition: impl AsRef<str>) -> anyhow::Result<Self> {
+        let path = Self::path(game, edition)?;
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let values = serde_json::from_slice::<Map<String, Json>>(&std::fs::read(&path)?)?;
+
+        Ok(Self {
+            values
+        })
+    }
+
+    pub fn save_for_game(&self, game: impl AsRef<str>, edition: impl AsRef<str>) -> anyhow::Result<()> {
+        let path = Self::path(game, edition)?;
+
+        let data = serde_json::to_vec(&self.values)?;
+
+        if data.len() > MAX_STORAGE_SIZE {
+            anyhow::bail!("Integration storage size limit exceeded: {} > {MAX_STORAGE_SIZE} bytes", data.len());
+        }
+
+        if let Some(folder_path) = path.parent() {
+            if !folder_path.exists() {
+                std::fs::create_dir_all(folder_path)?;
+            }
+        }
+
+        std::fs::write(path, data)?;
+
+        Ok(())
+    }
+
+    /// Remove all the values stored by the integration script
+    pub fn wipe_for_game(game: impl AsRef<str>, edition: impl AsRef<str>) -> anyhow::Result<()> {
+        let path = Self::path(game, edition)?;
+
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Deserialize};
+
+use anime_game_core::network::minreq;
+
+pub use minreq::Method;
+
+use crate::HTTP_CACHE_FOLDER;
+
+/// Max size of the cached responses on the disk, in bytes
+///
+/// Least recently stored responses are removed when it's exceeded
+const MAX_CACHE_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: Option<Method>,
+    pub headers: HashMap<String, String>,
+    pub body: Option<Vec<u8>>,
+
+    /// Request timeout, in seconds
+    pub timeout: Option<u64>,
+
+    /// Ignore cached response even if it's still fresh
+    pub refresh: bool,
+
+    /// Return cached response if the server is not reachable
+    pub allow_stale: bool
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Response {
+    pub url: String,
+    pub status: i32,
+    pub status_text: String,
+    pub headers: HashMap<String, String>,
+
+    #[serde(skip)]
+    pub body: Vec<u8>
+}
+
+impl Response {
+    #[inline]
+    pub fn is_ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    #[inline]
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> anyhow::Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    response: Response,
+
+    /// Time when the response was received or revalidated, in seconds
+    stored_at: u64,
+
+    /// Amount of seconds the response stays fresh
+    max_age: u64
+}
+
+impl Entry {
+    fn is_fresh(&self) -> bool {
+        now().saturating_sub(self.stored_at) < self.max_age
+    }
+}
+
+/// Parsed `Cache-Control` response header
+struct CacheControl {
+    no_store: bool,
+    max_age: u64
+}
+
+impl CacheControl {
+    fn from_headers(headers: &HashMap<String, String>) -> Self {
+        let mut control = Self {
+            no_store: false,
+            max_age: 0
+        };
+
+        // Cache key doesn't include request headers, so responses
+        // which depend on them can't be stored. Accept-Encoding
+        // is the same for all the launcher requests, and requests
+        // with custom headers are not cached at all
+        if let Some(vary) = headers.get("vary") {
+            control.no_store = vary.split(',')
+                .any(|header| !header.trim().eq_ignore_ascii_case("accept-encoding"));
+        }
+
+        let Some(value) = headers.get("cache-control") else {
+            return control;
+        };
+
+        let mut no_cache = false;
+
+        for directive in value.split(',') {
+            let directive = directive.trim().to_ascii_lowercase();
+
+            if directive == "no-store" {
+                control.no_store = true;
+            }
+
+            // `no-cache` responses can be stored but must be revalidated every time
+            else if directive == "no-cache" {
+                no_cache = true;
+            }
+
+            else if let Some(max_age) = directive.strip_prefix("max-age=") {
+                control.max_age = max_age.trim_matches('"').parse().unwrap_or(0);
+            }
+        }
+
+        if no_cache {
+            control.max_age = 0;
+        }
+
+        control
+    }
+}
+
+#[inline]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default()
+}
+
+/// Get path to the cache entry files of the given uri
+fn entry_path(uri: &str) -> PathBuf {
+    HTTP_CACHE_FOLDER.join(format!("{:x}", xxhash_rust::xxh3::xxh3_64(uri.as_bytes())))
+}
+
+fn load_entry(uri: &str) -> Option<Entry> {
+    let path = entry_path(uri);
+
+    let metadata = std::fs::read(path.with_extension("json")).ok()?;
+
+    let mut entry = serde_json::from_slice::<Entry>(&metadata).ok()?;
+
+    entry.response.body = std::fs::read(path.with_extension("body")).ok()?;
+
+    Some(entry)
+}
+
+/// Write file through a temporary one so readers never see it partially written
+fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+
+    temp_path.push(".tmp");
+
+    std::fs::write(&temp_path, data)?;
+    std::fs::rename(temp_path, path)
+}
+
+fn save_entry(uri: &str, entry: &Entry) -> anyhow::Result<()> {
+    let path = entry_path(uri);
+
+    std::fs::create_dir_all(HTTP_CACHE_FOLDER.as_path())?;
+
+    write_atomic(&path.with_extension("body"), &entry.response.body)?;
+    write_atomic(&path.with_extension("json"), &serde_json::to_vec(entry)?)?;
+
+    if let Err(err) = evict() {
+        tracing::warn!(?err, "Failed to evict cached responses");
+    }
+
+    Ok(())
+}
+
+/// Remove least recently stored responses while
+/// the cache folder is larger than `MAX_CACHE_SIZE`
+fn evict() -> anyhow::Result<()> {
+    let mut entries = HashMap::<String, (Vec<PathBuf>, u64, SystemTime)>::new();
+    let mut total = 0;
+
+    for file in HTTP_CACHE_FOLDER.read_dir()?.flatten() {
+        let Ok(metadata) = file.metadata() else {
+            continue;
+        };
+
+        let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+
+        // Body, metadata and temporary files of the same response share the name
+        let name = file.file_name()
+            .to_string_lossy()
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        let entry = entries.entry(name)
+            .or_insert((Vec::new(), 0, modified));
+
+        entry.0.push(file.path());
+        entry.1 += metadata.len();
+        entry.2 = entry.2.max(modified);
+
+        total += metadata.len();
+    }
+
+    if total <= MAX_CACHE_SIZE {
+        return Ok(());
+    }
+
+    let mut entries = entries.into_values().collect::<Vec<_>>();
+
+    entries.sort_by_key(|(_, _, modified)| *modified);
+
+    for (files, size, _) in entries {
+        if total <= MAX_CACHE_SIZE {
+            break;
+        }
+
+        for file in files {
+            let _ = std::fs::remove_file(file);
+        }
+
+        total = total.saturating_sub(size);
+    }
+
+    Ok(())
+}
+
+fn send(uri: &str, request: Request, cached: Option<&Entry>) -> anyhow::Result<Response> {
+    let mut http_request = minreq::Request::new(request.method.unwrap_or(Method::Get), uri);
+
+    for (key, value) in request.headers {
+        http_request = http_request.with_header(key, value);
+    }
+
+    // Ask the server to not send the body if the cached one is still valid
+    if let Some(entry) = cached {
+        if let Some(etag) = entry.response.headers.get("etag") {
+            http_request = http_request.with_header("If-None-Match", etag);
+        }
+
+        if let Some(last_modified) = entry.response.headers.get("last-modified") {
+            http_request = http_request.with_header("If-Modified-Since", last_modified);
+        }
+    }
+
+    if let Some(body) = request.body {
+        http_request = http_request.with_body(body);
+    }
+
+    if let Some(timeout) = request.timeout {
+        http_request = http_request.with_timeout(timeout);
+    }
+
+    let response = http_request.send()?;
+
+    Ok(Response {
+        url: response.url.clone(),
+        status: response.status_code,
+        status_text: response.reason_phrase.clone(),
+        headers: response.headers.clone(),
+        body: response.into_bytes()
+    })
+}
+
+/// Send HTTP request using the launcher's disk cache
+///
+/// Only `GET` requests without body and custom headers are cached. Cached responses
+/// are returned while they're fresh according to the `Cache-Control`
+/// header, and revalidated using `ETag` and `Last-Modified` headers otherwise
+pub fn fetch(uri: impl AsRef<str>, request: Request) -> anyhow::Result<Response> {
+    let uri = uri.as_ref();
+
+    let cacheable = matches!(request.method, None | Some(Method::Get))
+        && request.body.is_none()
+        && request.headers.is_empty();
+
+    if !cacheable {
+        return send(uri, request, None);
+    }
+
+    let cached = load_entry(uri);
+
+    if let Some(entry) = &cached {
+        if !request.refresh && entry.is_fresh() {
+            tracing::trace!(?uri, "Using cached response");
+
+            return Ok(entry.response.clone());
+        }
+    }
+
+    let allow_stale = request.allow_stale;
+
+    let response = match send(uri, request, cached.as_ref()) {
+        Ok(response) if response.status >= 500 && allow_stale && cached.is_some() => {
+            tracing::warn!(?uri, status = response.status, "Server error. Using stale cached response");
+
+            return Ok(cached.unwrap().response);
+        }
+
+        Ok(response) => response,
+
+        Err(err) => {
+            if let (true, Some(entry)) = (allow_stale, cached) {
+                tracing::warn!(?uri, ?err, "Failed to send request. Using stale cached response");
+
+                return Ok(entry.response);
+            }
+
+            return Err(err);
+        }
+    };
+
+    let control = CacheControl::from_headers(&response.headers);
+
+    // Cached response is still valid
+    if response.status == 304 {
+        if let Some(mut entry) = cached {
+            entry.stored_at = now();
+            entry.max_age = control.max_age;
+
+            if let Err(err) = save_entry(uri, &entry) {
+                tracing::warn!(?uri, ?err, "Failed to update cached response");
+            }
+
+            return Ok(entry.response);
+        }
+    }
+
+    if response.is_ok() && !control.no_store {
+        let entry = Entry {
+            response,
+            stored_at: now(),
+            max_age: control.max_age
+        };
+
+        if let Err(err) = save_entry(uri, &entry) {
+            tracing::warn!(?uri, ?err, "Failed to cache response");
+        }
+
+        return Ok(entry.response);
+    }
+
+    Ok(response)
+}
+
+#[inline]
+/// Send `GET` request using the launcher's disk cache
+///
+/// Stale cached response is returned if the server is not reachable
+pub fn get(uri: impl AsRef<str>) -> anyhow::Result<Response> {
+    fetch(uri, Request {
+        allow_stale: true,
+        ..Request::default()
+    })
+}
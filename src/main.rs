@@ -4,6 +4,7 @@ use relm4::prelude::*;
 
 pub mod i18n;
 pub mod utils;
+pub mod http_cache;
 pub mod config;
 pub mod games;
 pub mod components;
@@ -42,6 +43,9 @@ lazy_static::lazy_static! {
     /// Path to the launcher's config file
     pub static ref CONFIG_FILE: PathBuf = LAUNCHER_FOLDER.join("config.json");
 
+    /// Cached HTTP responses folder
+    pub static ref HTTP_CACHE_FOLDER: PathBuf = LAUNCHER_FOLDER.join("cache").join("http");
+
     /// Path to launcher's debug log file
     pub static ref DEBUG_FILE: PathBuf = LAUNCHER_FOLDER.join("debug.log");
 }
@@ -50,6 +54,13 @@ fn main() -> anyhow::Result<()> {
     // Setup custom panic handler
     human_panic::setup_panic!(human_panic::metadata!());
 
+    // Run integration conformance checks without the UI
+    let args = std::env::args().collect::<Vec<_>>();
+
+    if let Some(pos) = args.iter().position(|arg| arg == "--check-integration") {
+        std::process::exit(check_integration(&args[pos + 1..])?);
+    }
+
     adw::init().expect("Libadwaita initialization failed");
 
     // Register and include resources
@@ -87,3 +98,50 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Run integration conformance checks and return process exit code
+/// 
+/// Usage: `--check-integration <manifest> [--game-path <path>] [--addons-path <path>] [--fixtures <path>] [--record]`
+/// 
+/// Network built-ins never perform real requests
+/// unless `--record` is given
+fn check_integration(args: &[String]) -> anyhow::Result<i32> {
+    use games::integrations::builtins::network::{self, NetworkMode};
+
+    let Some(manifest) = args.first() else {
+        anyhow::bail!("Integration manifest path expected");
+    };
+
+    let get_arg = |name: &str| args.iter()
+        .position(|arg| arg == name)
+        .and_then(|pos| args.get(pos + 1))
+        .map(PathBuf::from);
+
+    let fixtures_path = std::env::temp_dir().join("anime-games-launcher-conformance");
+
+    let game_path = get_arg("--game-path").unwrap_or_else(|| fixtures_path.join("game"));
+    let addons_path = get_arg("--addons-path").unwrap_or_else(|| fixtures_path.join("addons"));
+
+    std::fs::create_dir_all(&game_path)?;
+    std::fs::create_dir_all(&addons_path)?;
+
+    let fixtures = get_arg("--fixtures");
+
+    // Network requests are replayed from the recorded fixtures,
+    // so requests without them fail the checks
+    if args.iter().any(|arg| arg == "--record") {
+        let Some(fixtures) = fixtures else {
+            anyhow::bail!("--record requires --fixtures folder");
+        };
+
+        network::set_mode(NetworkMode::Record(fixtures));
+    } else {
+        network::set_mode(NetworkMode::Replay(fixtures.unwrap_or_else(|| fixtures_path.join("fixtures"))));
+    }
+
+    let report = games::integrations::conformance::run(manifest, game_path, addons_path);
+
+    report.print();
+
+    Ok(if report.is_success() { 0 } else { 1 })
+}
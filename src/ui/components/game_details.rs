@@ -1,14 +1,29 @@
+use serde_json::Value as Json;
+
 use relm4::prelude::*;
 use gtk::prelude::*;
+use adw::prelude::*;
 
 use crate::tr;
 
+use crate::config;
+use crate::games;
+
 use crate::games::metadata::LauncherMetadata;
+use crate::games::storage::IntegrationStorage;
+use crate::games::news::NewsCache;
 use crate::games::integrations::standards::game::{
     Status,
     StatusSeverity
 };
 
+use crate::games::integrations::standards::settings::{
+    Setting,
+    SettingKind
+};
+
+use crate::games::integrations::standards::news::News;
+
 use crate::ui::components::game_card::{
     CardInfo,
     CardComponent,
@@ -24,24 +39,57 @@ pub struct GameDetailsComponent {
 
     pub installed: bool,
     pub running: bool,
-    pub status: Option<Status>
+    pub status: Option<Status>,
+
+    /// Upcoming game version available for pre-download
+    pub predownload: Option<String>,
+
+    /// Options declared by the integration script
+    pub settings_group: adw::PreferencesGroup,
+    pub settings_rows: Vec<adw::PreferencesRow>,
+
+    /// News of the current game edition
+    pub news_list: gtk::ListBox,
+    pub news: Vec<News>
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum GameDetailsComponentInput {
     SetInfo(CardInfo),
     SetMetadata(LauncherMetadata),
     SetInstalled(bool),
     SetRunning(bool),
     SetStatus(Option<Status>),
+    SetPredownload(Option<String>),
 
     EditCard(CardComponentInput),
 
     EmitDownloadGame,
+    EmitPredownloadGame,
     EmitVerifyGame,
     EmitLaunchGame,
     EmitKillGame,
-    EmitOpenAddonsManager
+    EmitOpenAddonsManager,
+    EmitWipeStorage,
+
+    SetSettingValue {
+        name: String,
+        value: Json
+    },
+
+    SetSettings {
+        info: CardInfo,
+
+        /// Options with their current values
+        settings: Vec<(Setting, Json)>
+    },
+
+    SetNews {
+        info: CardInfo,
+
+        /// News with their pictures content
+        news: Vec<(News, Option<Vec<u8>>)>
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -50,6 +98,7 @@ pub enum GameDetailsComponentOutput {
     ShowTasksFlap,
 
     DownloadGame(CardInfo),
+    PredownloadGame(CardInfo),
     VerifyGame(CardInfo),
     LaunchGame(CardInfo),
     KillGame(CardInfo),
@@ -199,6 +248,33 @@ impl SimpleAsyncComponent for GameDetailsComponent {
                             set_visible: !model.running,
 
                             connect_clicked => GameDetailsComponentInput::EmitVerifyGame
+                        },
+
+                        gtk::Button {
+                            adw::ButtonContent {
+                                set_icon_name: "folder-download-symbolic",
+
+                                #[watch]
+                                set_label: &match &model.predownload {
+                                    Some(version) if model.is_predownloaded() => tr!("details-predownloaded", {
+                                        "version" = version.clone()
+                                    }),
+
+                                    version => tr!("details-predownload", {
+                                        "version" = version.clone().unwrap_or_default()
+                                    })
+                                }
+                            },
+
+                            add_css_class: "pill",
+
+                            #[watch]
+                            set_visible: model.predownload.is_some(),
+
+                            #[watch]
+                            set_sensitive: !model.is_predownloaded(),
+
+                            connect_clicked => GameDetailsComponentInput::EmitPredownloadGame
                         }
                     },
 
@@ -221,9 +297,66 @@ impl SimpleAsyncComponent for GameDetailsComponent {
 
                             connect_clicked => GameDetailsComponentInput::EmitOpenAddonsManager
                         },
+
+                        gtk::Button {
+                            adw::ButtonContent {
+                                set_icon_name: "user-trash-symbolic",
+                                set_label: &tr!("details-wipe-storage")
+                            },
+
+                            add_css_class: "pill",
+
+                            #[watch]
+                            set_visible: !model.running,
+
+                            connect_clicked => GameDetailsComponentInput::EmitWipeStorage
+                        }
+                    }
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+
+                    set_margin_top: 36,
+                    set_spacing: 8,
+
+                    #[watch]
+                    set_visible: !model.news.is_empty(),
+
+                    gtk::Label {
+                        set_halign: gtk::Align::Start,
+
+                        add_css_class: "heading",
+
+                        set_label: &tr!("details-news")
+                    },
+
+                    gtk::ScrolledWindow {
+                        set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                        set_propagate_natural_height: true,
+                        set_max_content_height: 280,
+                        set_min_content_width: 420,
+
+                        #[local_ref]
+                        news_list -> gtk::ListBox {
+                            set_selection_mode: gtk::SelectionMode::None,
+
+                            add_css_class: "boxed-list"
+                        }
                     }
                 },
 
+                #[local_ref]
+                settings_group -> adw::PreferencesGroup {
+                    set_margin_top: 36,
+
+                    set_title: &tr!("details-game-settings"),
+
+                    #[watch]
+                    set_visible: !model.settings_rows.is_empty() && !model.running
+                },
+
                 gtk::Box {
                     set_orientation: gtk::Orientation::Vertical,
 
@@ -266,9 +399,19 @@ impl SimpleAsyncComponent for GameDetailsComponent {
 
             installed: false,
             running: false,
-            status: None
+            status: None,
+            predownload: None,
+
+            settings_group: adw::PreferencesGroup::new(),
+            settings_rows: Vec::new(),
+
+            news_list: gtk::ListBox::new(),
+            news: Vec::new()
         };
 
+        let settings_group = &model.settings_group;
+        let news_list = &model.news_list;
+
         model.game_card.emit(CardComponentInput::SetClickable(false));
         model.game_card.emit(CardComponentInput::SetDisplayTitle(false));
 
@@ -283,6 +426,9 @@ impl SimpleAsyncComponent for GameDetailsComponent {
                 self.info = info.clone();
 
                 self.game_card.emit(CardComponentInput::SetInfo(info));
+
+                self.update_settings(&sender);
+                self.update_news(&sender);
             }
 
             GameDetailsComponentInput::SetMetadata(metadata) => self.metadata = metadata,
@@ -295,6 +441,7 @@ impl SimpleAsyncComponent for GameDetailsComponent {
 
             GameDetailsComponentInput::SetRunning(running) => self.running = running,
             GameDetailsComponentInput::SetStatus(status) => self.status = status,
+            GameDetailsComponentInput::SetPredownload(version) => self.predownload = version,
 
             GameDetailsComponentInput::EditCard(message) => self.game_card.emit(message),
 
@@ -305,6 +452,13 @@ impl SimpleAsyncComponent for GameDetailsComponent {
                 sender.output(GameDetailsComponentOutput::ShowTasksFlap).unwrap();
             }
 
+            GameDetailsComponentInput::EmitPredownloadGame => {
+                sender.output(GameDetailsComponentOutput::PredownloadGame(self.info.clone())).unwrap();
+
+                sender.output(GameDetailsComponentOutput::HideDetails).unwrap();
+                sender.output(GameDetailsComponentOutput::ShowTasksFlap).unwrap();
+            }
+
             GameDetailsComponentInput::EmitVerifyGame => {
                 sender.output(GameDetailsComponentOutput::VerifyGame(self.info.clone())).unwrap();
 
@@ -323,6 +477,438 @@ impl SimpleAsyncComponent for GameDetailsComponent {
             GameDetailsComponentInput::EmitOpenAddonsManager => {
                 sender.output(GameDetailsComponentOutput::OpenAddonsManager(self.info.clone())).unwrap();
             }
+
+            GameDetailsComponentInput::EmitWipeStorage => {
+                let toast = match IntegrationStorage::wipe_for_game(self.info.get_name(), self.info.get_edition()) {
+                    Ok(()) => GameDetailsComponentOutput::ShowToast {
+                        title: tr!("details-storage-wiped", {
+                            "game-title" = self.info.get_title()
+                        }),
+                        message: None
+                    },
+
+                    Err(err) => {
+                        tracing::error!("Failed to wipe integration storage: {err}");
+
+                        GameDetailsComponentOutput::ShowToast {
+                            title: tr!("game-wipe-storage-failed", {
+                                "game-title" = self.info.get_title()
+                            }),
+                            message: Some(err.to_string())
+                        }
+                    }
+                };
+
+                sender.output(toast).unwrap();
+            }
+
+            GameDetailsComponentInput::SetSettingValue { name, value } => {
+                let property = format!("games.settings.{}.values.{}.{name}", self.info.get_name(), self.info.get_edition());
+
+                if let Err(err) = config::set(property, value) {
+                    tracing::error!("Failed to update game setting: {err}");
+
+                    sender.output(GameDetailsComponentOutput::ShowToast {
+                        title: tr!("config-property-update-failed"),
+                        message: Some(err.to_string())
+                    }).unwrap();
+                }
+            }
+
+            GameDetailsComponentInput::SetSettings { info, settings } => {
+                // Settings of the previously opened game could be loaded too late
+                if info != self.info {
+                    return;
+                }
+
+                for row in self.settings_rows.drain(..) {
+                    self.settings_group.remove(&row);
+                }
+
+                for (setting, value) in settings {
+                    let row = setting_row(&setting, &value, &sender);
+
+                    self.settings_group.add(&row);
+                    self.settings_rows.push(row);
+                }
+            }
+
+            GameDetailsComponentInput::SetNews { info, news } => {
+                // News of the previously opened game could be loaded too late
+                if info != self.info {
+                    return;
+                }
+
+                self.news_list.remove_all();
+
+                self.news = news.iter()
+                    .map(|(news, _)| news.clone())
+                    .collect();
+
+                for (news, picture) in news {
+                    self.news_list.append(&news_row(news, picture));
+                }
+            }
+        }
+    }
+}
+
+impl GameDetailsComponent {
+    #[inline]
+    /// Check if the upcoming game version is already pre-downloaded
+    fn is_predownloaded(&self) -> bool {
+        match (&self.predownload, &self.metadata.predownload) {
+            (Some(version), Some(predownload)) => &predownload.version == version,
+            _ => false
         }
     }
+
+    /// Request options declared by the current game edition
+    /// in background and replace rows of the settings group by them
+    fn update_settings(&mut self, sender: &AsyncComponentSender<Self>) {
+        for row in self.settings_rows.drain(..) {
+            self.settings_group.remove(&row);
+        }
+
+        let info = self.info.clone();
+        let sender = sender.clone();
+
+        std::thread::spawn(move || {
+            let Ok(Some(game)) = games::get(info.get_name()) else {
+                return;
+            };
+
+            let settings = game.driver.get_settings_schema(info.get_edition())
+                .and_then(|schema| {
+                    game.driver.get_settings_values(info.get_edition())
+                        .map(|values| (schema, values))
+                });
+
+            let (schema, values) = match settings {
+                Ok(settings) => settings,
+
+                Err(err) => {
+                    tracing::error!("Failed to get game settings: {err}");
+
+                    let _ = sender.output(GameDetailsComponentOutput::ShowToast {
+                        title: tr!("game-get-settings-failed", {
+                            "game-title" = info.get_title()
+                        }),
+                        message: Some(err.to_string())
+                    });
+
+                    return;
+                }
+            };
+
+            let settings = schema.into_iter()
+                .map(|setting| {
+                    let value = values.get(&setting.name)
+                        .cloned()
+                        .unwrap_or_else(|| setting.default.clone());
+
+                    (setting, value)
+                })
+                .collect();
+
+            sender.input(GameDetailsComponentInput::SetSettings {
+                info,
+                settings
+            });
+        });
+    }
+
+    /// Show cached news of the current game edition
+    /// and request the latest ones in background
+    fn update_news(&mut self, sender: &AsyncComponentSender<Self>) {
+        self.news_list.remove_all();
+        self.news.clear();
+
+        let info = self.info.clone();
+        let sender = sender.clone();
+
+        std::thread::spawn(move || {
+            let cached = match NewsCache::load_for_game(info.get_name(), info.get_edition()) {
+                Ok(cache) => cache.news,
+
+                Err(err) => {
+                    tracing::error!("Failed to load cached game news: {err}");
+
+                    Vec::new()
+                }
+            };
+
+            if !cached.is_empty() {
+                sender.input(GameDetailsComponentInput::SetNews {
+                    info: info.clone(),
+                    news: load_pictures(info.get_name(), &cached)
+                });
+            }
+
+            let Ok(Some(game)) = games::get(info.get_name()) else {
+                return;
+            };
+
+            let news = match game.driver.get_news(info.get_edition()) {
+                Ok(news) => news,
+
+                // Keep cached news when offline
+                Err(err) => {
+                    tracing::error!("Failed to get game news: {err}");
+
+                    return;
+                }
+            };
+
+            if news == cached {
+                return;
+            }
+
+            let cache = NewsCache { news };
+
+            if let Err(err) = cache.save_for_game(info.get_name(), info.get_edition()) {
+                tracing::error!("Failed to cache game news: {err}");
+            }
+
+            sender.input(GameDetailsComponentInput::SetNews {
+                news: load_pictures(info.get_name(), &cache.news),
+                info
+            });
+        });
+    }
+}
+
+/// Read pictures of the news from the disk or the HTTP cache
+/// 
+/// Local pictures can only be read from the integration folder
+fn load_pictures(game_name: &str, news: &[News]) -> Vec<(News, Option<Vec<u8>>)> {
+    let integration_path = config::get().games.integrations.path
+        .join(game_name)
+        .canonicalize()
+        .ok();
+
+    news.iter()
+        .map(|news| {
+            let picture = news.image.as_ref().and_then(|image| {
+                if image.starts_with("http://") || image.starts_with("https://") {
+                    match crate::http_cache::get(image) {
+                        Ok(response) if response.is_ok() => Some(response.body),
+
+                        Ok(response) => {
+                            tracing::warn!("Failed to load news picture {image}: {}", response.status);
+
+                            None
+                        }
+
+                        Err(err) => {
+                            tracing::warn!("Failed to load news picture {image}: {err}");
+
+                            None
+                        }
+                    }
+                }
+
+                else {
+                    let integration_path = integration_path.as_ref()?;
+
+                    // Relative paths (e.g. manifest assets) are resolved
+                    // from the integration folder, absolute ones are kept
+                    let path = integration_path.join(image).canonicalize().ok()?;
+
+                    if path.starts_with(integration_path) {
+                        std::fs::read(path).ok()
+                    }
+
+                    else {
+                        tracing::warn!("News picture {image} is outside of the integration folder");
+
+                        None
+                    }
+                }
+            });
+
+            (news.clone(), picture)
+        })
+        .collect()
+}
+
+/// Build list row of the news item
+fn news_row(news: News, picture: Option<Vec<u8>>) -> adw::ActionRow {
+    let row = adw::ActionRow::new();
+
+    // News text is not a pango markup
+    row.set_use_markup(false);
+
+    row.set_title(&news.title);
+    row.set_title_lines(2);
+    row.set_subtitle_lines(3);
+
+    let subtitle = [news.date, news.summary]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" · ");
+
+    row.set_subtitle(&subtitle);
+
+    let texture = picture.and_then(|picture| {
+        gtk::gdk::Texture::from_bytes(&gtk::glib::Bytes::from_owned(picture)).ok()
+    });
+
+    if let Some(texture) = texture {
+        let picture = gtk::Picture::for_paintable(&texture);
+
+        picture.set_content_fit(gtk::ContentFit::Cover);
+        picture.set_size_request(96, 54);
+        picture.set_valign(gtk::Align::Center);
+
+        picture.add_css_class("card");
+
+        row.add_prefix(&picture);
+    }
+
+    // Other schemes could run local files outside of the sandbox
+    let uri = news.uri.filter(|uri| uri.starts_with("http://") || uri.starts_with("https://"));
+
+    if let Some(uri) = uri {
+        row.set_activatable(true);
+
+        row.add_suffix(&gtk::Image::from_icon_name("adw-external-link-symbolic"));
+
+        row.connect_activated(move |_| {
+            if let Err(err) = open::that(&uri) {
+                tracing::error!("Failed to open news link: {err}");
+            }
+        });
+    }
+
+    row
+}
+
+/// Build preferences row changing given setting
+fn setting_row(setting: &Setting, value: &Json, sender: &AsyncComponentSender<GameDetailsComponent>) -> adw::PreferencesRow {
+    let name = setting.name.clone();
+    let sender = sender.clone();
+
+    let set_value = move |value: Json| {
+        sender.input(GameDetailsComponentInput::SetSettingValue {
+            name: name.clone(),
+            value
+        });
+    };
+
+    let row: adw::PreferencesRow = match &setting.kind {
+        SettingKind::Bool => {
+            let row = adw::SwitchRow::new();
+
+            row.set_active(value.as_bool().unwrap_or_default());
+
+            row.connect_active_notify(move |row| set_value(Json::from(row.is_active())));
+
+            row.upcast()
+        }
+
+        SettingKind::Number { min, max, step } => {
+            let step = step.unwrap_or(1.0);
+
+            let row = adw::SpinRow::new(
+                Some(&gtk::Adjustment::new(
+                    value.as_f64().unwrap_or_default(),
+                    min.unwrap_or(f64::MIN),
+                    max.unwrap_or(f64::MAX),
+                    step, step, 0.0
+                )),
+                step,
+                if step.fract() == 0.0 { 0 } else { 2 }
+            );
+
+            row.connect_value_notify(move |row| set_value(Json::from(row.value())));
+
+            row.upcast()
+        }
+
+        SettingKind::Enum(variants) => {
+            let row = adw::ComboRow::new();
+
+            let titles = variants.iter()
+                .map(|variant| variant.title.as_str())
+                .collect::<Vec<_>>();
+
+            row.set_model(Some(&gtk::StringList::new(&titles)));
+
+            if let Some(selected) = variants.iter().position(|variant| Some(variant.value.as_str()) == value.as_str()) {
+                row.set_selected(selected as u32);
+            }
+
+            let variants = variants.clone();
+
+            row.connect_selected_notify(move |row| {
+                if let Some(variant) = variants.get(row.selected() as usize) {
+                    set_value(Json::from(variant.value.as_str()));
+                }
+            });
+
+            row.upcast()
+        }
+
+        SettingKind::String => {
+            let row = adw::EntryRow::new();
+
+            row.set_text(value.as_str().unwrap_or_default());
+            row.set_show_apply_button(true);
+
+            row.connect_apply(move |row| set_value(Json::from(row.text().as_str())));
+
+            row.upcast()
+        }
+
+        SettingKind::Path { folder } => {
+            let row = adw::EntryRow::new();
+
+            row.set_text(value.as_str().unwrap_or_default());
+            row.set_show_apply_button(true);
+
+            let button = gtk::Button::from_icon_name("document-open-symbolic");
+
+            button.set_valign(gtk::Align::Center);
+            button.add_css_class("flat");
+
+            let folder = *folder;
+
+            button.connect_clicked(gtk::glib::clone!(@weak row => move |button| {
+                let dialog = gtk::FileDialog::new();
+
+                let window = button.root()
+                    .and_then(|root| root.downcast::<gtk::Window>().ok());
+
+                let callback = gtk::glib::clone!(@weak row => move |file: Result<gtk::gio::File, gtk::glib::Error>| {
+                    if let Some(path) = file.ok().and_then(|file| file.path()) {
+                        row.set_text(&path.to_string_lossy());
+
+                        row.emit_by_name::<()>("apply", &[]);
+                    }
+                });
+
+                if folder {
+                    dialog.select_folder(window.as_ref(), gtk::gio::Cancellable::NONE, callback);
+                } else {
+                    dialog.open(window.as_ref(), gtk::gio::Cancellable::NONE, callback);
+                }
+            }));
+
+            row.add_suffix(&button);
+
+            row.connect_apply(move |row| set_value(Json::from(row.text().as_str())));
+
+            row.upcast()
+        }
+    };
+
+    row.set_title(&setting.title);
+
+    if let Some(description) = &setting.description {
+        row.set_tooltip_text(Some(description));
+    }
+
+    row
 }
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{
     AtomicU64,
@@ -23,6 +23,7 @@ use crate::ui::components::game_card::CardInfo;
 
 use crate::games;
 use crate::games::integrations::standards::diff::DiffInfo;
+use crate::games::metadata::{LauncherMetadata, GamePredownloadMetadata};
 
 use crate::config;
 
@@ -41,6 +42,23 @@ pub enum DiffOrigin {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DiffMode {
+    /// Download diff files and apply them
+    /// 
+    /// Version is set for the game updates so
+    /// their pre-downloaded files can be reused
+    Apply {
+        version: Option<String>
+    },
+
+    /// Download diff files of the upcoming version
+    /// without applying them
+    Predownload {
+        version: String
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Status {
     PreparingTransition,
@@ -57,6 +75,7 @@ pub struct DownloadDiffQueuedTask {
     pub card_info: CardInfo,
     pub diff_info: DiffInfo,
     pub diff_origin: DiffOrigin,
+    pub diff_mode: DiffMode,
     pub download_path: PathBuf
 }
 
@@ -74,6 +93,7 @@ impl QueuedTask for DownloadDiffQueuedTask {
 
         let diff_info = self.diff_info.clone();
         let diff_origin = self.diff_origin.clone();
+        let diff_mode = self.diff_mode.clone();
 
         let download_path = self.download_path.clone();
 
@@ -90,8 +110,42 @@ impl QueuedTask for DownloadDiffQueuedTask {
 
                     sender.send((Status::PreparingTransition, 0, 1))?;
 
+                    if matches!(diff_mode, DiffMode::Predownload { .. }) && diff_origin != DiffOrigin::Game {
+                        anyhow::bail!("Only game diffs can be pre-downloaded");
+                    }
+
+                    let version = match &diff_mode {
+                        DiffMode::Apply { version } => version.clone(),
+                        DiffMode::Predownload { version } => Some(version.clone())
+                    };
+
+                    // Game updates are staged by the pre-download task
+                    // in the transition of their target version
+                    let transition_name = match (&diff_origin, &version) {
+                        (DiffOrigin::Game, Some(version)) => format!("download-diff:{game_name}:{game_edition}:{version}"),
+
+                        _ => format!("download-diff:{game_name}:{game_edition}:{:?}:{:?}", diff_origin, diff_info)
+                    };
+
+                    let predownload = match (&diff_origin, &version) {
+                        (DiffOrigin::Game, Some(_)) => LauncherMetadata::load_for_game(&game_name, &game_edition)?.predownload,
+                        _ => None
+                    };
+
+                    // Diff files could be already downloaded
+                    // into this transition by the pre-download task
+                    let predownloaded = match (&predownload, &version) {
+                        (Some(predownload), Some(version)) => &predownload.version == version && predownload.transition == transition_name,
+                        _ => false
+                    };
+
+                    // Files staged for another version can't be applied anymore
+                    if let Some(predownload) = predownload.filter(|_| !predownloaded) {
+                        remove_predownload(&game_name, &game_edition, &download_path, &predownload)?;
+                    }
+
                     let transition = Transition::get_in(
-                        format!("download-diff:{game_name}:{game_edition}:{:?}:{:?}", diff_origin, diff_info),
+                        transition_name.clone(),
                         &download_path,
                         config.general.transitions.path
                     )?;
@@ -101,12 +155,21 @@ impl QueuedTask for DownloadDiffQueuedTask {
                     // Run pre-transition code
 
                     match &diff_origin {
+                        _ if matches!(diff_mode, DiffMode::Predownload { .. }) => (),
+
                         DiffOrigin::Game if game.driver.has_game_diff_pre_transition()? => {
                             sender.send((Status::RunPreTransitionCode, 0, 1))?;
 
-                            game.driver.run_game_diff_pre_transition(
-                                &transition.original_path().to_string_lossy(),
-                                &game_edition
+                            let progress_sender = sender.clone();
+
+                            game.driver.with_progress(
+                                move |current, total| {
+                                    let _ = progress_sender.send((Status::RunPreTransitionCode, current, total));
+                                },
+                                |driver| driver.run_game_diff_pre_transition(
+                                    &transition.original_path().to_string_lossy(),
+                                    &game_edition
+                                )
                             )?;
 
                             sender.send((Status::RunPreTransitionCode, 1, 1))?;
@@ -115,11 +178,18 @@ impl QueuedTask for DownloadDiffQueuedTask {
                         DiffOrigin::Addon { group_name, addon_name } if game.driver.has_addons_diff_pre_transition()? => {
                             sender.send((Status::RunPreTransitionCode, 0, 1))?;
 
-                            game.driver.run_addons_diff_pre_transition(
-                                group_name,
-                                addon_name,
-                                &transition.original_path().to_string_lossy(),
-                                &game_edition
+                            let progress_sender = sender.clone();
+
+                            game.driver.with_progress(
+                                move |current, total| {
+                                    let _ = progress_sender.send((Status::RunPreTransitionCode, current, total));
+                                },
+                                |driver| driver.run_addons_diff_pre_transition(
+                                    group_name,
+                                    addon_name,
+                                    &transition.original_path().to_string_lossy(),
+                                    &game_edition
+                                )
                             )?;
 
                             sender.send((Status::RunPreTransitionCode, 1, 1))?;
@@ -130,8 +200,16 @@ impl QueuedTask for DownloadDiffQueuedTask {
 
                     // Download and extract diff files
 
+                    let diff_info = if predownloaded {
+                        None
+                    } else {
+                        Some(diff_info)
+                    };
+
                     match diff_info {
-                        DiffInfo::Archive { size: _, uri } => {
+                        None => (),
+
+                        Some(DiffInfo::Archive { size: _, uri }) => {
                             // Download archive
 
                             let downloader = Downloader::new(uri);
@@ -172,7 +250,7 @@ impl QueuedTask for DownloadDiffQueuedTask {
                             std::fs::remove_file(archive)?;
                         }
 
-                        DiffInfo::Segments { size, segments } => {
+                        Some(DiffInfo::Segments { size, segments }) => {
                             // Download segments
 
                             let mut archives = vec![];
@@ -225,7 +303,7 @@ impl QueuedTask for DownloadDiffQueuedTask {
                             }
                         }
 
-                        DiffInfo::Files { size, files } => {
+                        Some(DiffInfo::Files { size, files }) => {
                             let pool = rusty_pool::Builder::new()
                                 .name(String::from("download_files"))
                                 .core_size(config.general.threads.number as usize)
@@ -271,15 +349,36 @@ impl QueuedTask for DownloadDiffQueuedTask {
                         }
                     }
 
+                    // Keep pre-downloaded files in the transition folder
+                    // until the version is released
+
+                    if let DiffMode::Predownload { version } = &diff_mode {
+                        update_predownload(&game_name, &game_edition, |predownload| {
+                            *predownload = Some(GamePredownloadMetadata {
+                                version: version.clone(),
+                                transition: transition_name
+                            });
+                        })?;
+
+                        return Ok(());
+                    }
+
                     // Run transition code
 
                     match &diff_origin {
                         DiffOrigin::Game if game.driver.has_game_diff_transition()? => {
                             sender.send((Status::RunTransitionCode, 0, 1))?;
 
-                            game.driver.run_game_diff_transition(
-                                &transition.transition_path().to_string_lossy(),
-                                &game_edition
+                            let progress_sender = sender.clone();
+
+                            game.driver.with_progress(
+                                move |current, total| {
+                                    let _ = progress_sender.send((Status::RunTransitionCode, current, total));
+                                },
+                                |driver| driver.run_game_diff_transition(
+                                    &transition.transition_path().to_string_lossy(),
+                                    &game_edition
+                                )
                             )?;
 
                             sender.send((Status::RunTransitionCode, 1, 1))?;
@@ -288,11 +387,18 @@ impl QueuedTask for DownloadDiffQueuedTask {
                         DiffOrigin::Addon { group_name, addon_name } if game.driver.has_addons_diff_transition()? => {
                             sender.send((Status::RunTransitionCode, 0, 1))?;
 
-                            game.driver.run_addons_diff_transition(
-                                group_name,
-                                addon_name,
-                                &transition.transition_path().to_string_lossy(),
-                                &game_edition
+                            let progress_sender = sender.clone();
+
+                            game.driver.with_progress(
+                                move |current, total| {
+                                    let _ = progress_sender.send((Status::RunTransitionCode, current, total));
+                                },
+                                |driver| driver.run_addons_diff_transition(
+                                    group_name,
+                                    addon_name,
+                                    &transition.transition_path().to_string_lossy(),
+                                    &game_edition
+                                )
                             )?;
 
                             sender.send((Status::RunTransitionCode, 1, 1))?;
@@ -307,6 +413,15 @@ impl QueuedTask for DownloadDiffQueuedTask {
 
                     transition.finish()?;
 
+                    // Pre-downloaded files were applied now
+                    if predownloaded {
+                        update_predownload(&game_name, &game_edition, |predownload| {
+                            if predownload.as_ref().map(|predownload| predownload.transition == transition_name).unwrap_or_default() {
+                                *predownload = None;
+                            }
+                        })?;
+                    }
+
                     sender.send((Status::FinishingTransition, 1, 1))?;
 
                     // Run post-transition code
@@ -315,9 +430,16 @@ impl QueuedTask for DownloadDiffQueuedTask {
                         DiffOrigin::Game if game.driver.has_game_diff_post_transition()? => {
                             sender.send((Status::RunPostTransitionCode, 0, 1))?;
 
-                            game.driver.run_game_diff_post_transition(
-                                &transition.original_path().to_string_lossy(),
-                                &game_edition
+                            let progress_sender = sender.clone();
+
+                            game.driver.with_progress(
+                                move |current, total| {
+                                    let _ = progress_sender.send((Status::RunPostTransitionCode, current, total));
+                                },
+                                |driver| driver.run_game_diff_post_transition(
+                                    &transition.original_path().to_string_lossy(),
+                                    &game_edition
+                                )
                             )?;
 
                             sender.send((Status::RunPostTransitionCode, 1, 1))?;
@@ -326,11 +448,18 @@ impl QueuedTask for DownloadDiffQueuedTask {
                         DiffOrigin::Addon { group_name, addon_name } if game.driver.has_addons_diff_post_transition()? => {
                             sender.send((Status::RunPostTransitionCode, 0, 1))?;
 
-                            game.driver.run_addons_diff_post_transition(
-                                group_name,
-                                addon_name,
-                                &transition.original_path().to_string_lossy(),
-                                &game_edition
+                            let progress_sender = sender.clone();
+
+                            game.driver.with_progress(
+                                move |current, total| {
+                                    let _ = progress_sender.send((Status::RunPostTransitionCode, current, total));
+                                },
+                                |driver| driver.run_addons_diff_post_transition(
+                                    group_name,
+                                    addon_name,
+                                    &transition.original_path().to_string_lossy(),
+                                    &game_edition
+                                )
                             )?;
 
                             sender.send((Status::RunPostTransitionCode, 1, 1))?;
@@ -346,6 +475,41 @@ impl QueuedTask for DownloadDiffQueuedTask {
     }
 }
 
+/// Remove files of the outdated pre-downloaded update and its record
+fn remove_predownload(game: &str, edition: &str, download_path: &Path, predownload: &GamePredownloadMetadata) -> anyhow::Result<()> {
+    tracing::info!("Removing outdated {game} {edition} pre-download of version {}", predownload.version);
+
+    let transition = Transition::get_in(
+        predownload.transition.clone(),
+        download_path,
+        config::get().general.transitions.path
+    )?;
+
+    let transition_path = transition.transition_path();
+
+    if transition_path.exists() {
+        std::fs::remove_dir_all(transition_path)?;
+    }
+
+    update_predownload(game, edition, |record| {
+        if record.as_ref() == Some(predownload) {
+            *record = None;
+        }
+    })
+}
+
+/// Change pre-download record of the game
+/// 
+/// Metadata is loaded right before saving since the game
+/// can be played while its update is being downloaded
+fn update_predownload(game: &str, edition: &str, update: impl FnOnce(&mut Option<GamePredownloadMetadata>)) -> anyhow::Result<()> {
+    let mut launcher_metadata = LauncherMetadata::load_for_game(game, edition)?;
+
+    update(&mut launcher_metadata.predownload);
+
+    launcher_metadata.save_for_game(game, edition)
+}
+
 #[derive(Debug)]
 pub struct DownloadDiffResolvedTask {
     pub updater: BasicUpdater<Status, (), anyhow::Error>,
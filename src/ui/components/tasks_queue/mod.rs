@@ -395,9 +395,10 @@ impl SimpleAsyncComponent for TasksQueueComponent {
 
                                 TaskStatus::DeletingFiles => (true, tr!("tasks-deleting-files")),
 
-                                TaskStatus::RunPreTransitionCode  => (true, tr!("tasks-pre-transition-code")),
-                                TaskStatus::RunTransitionCode     => (true, tr!("tasks-transition-code")),
-                                TaskStatus::RunPostTransitionCode => (true, tr!("tasks-post-transition-code")),
+                                // Transition code can report progress of the built-in functions
+                                TaskStatus::RunPreTransitionCode  => (task.get_total() <= 1, tr!("tasks-pre-transition-code")),
+                                TaskStatus::RunTransitionCode     => (task.get_total() <= 1, tr!("tasks-transition-code")),
+                                TaskStatus::RunPostTransitionCode => (task.get_total() <= 1, tr!("tasks-post-transition-code")),
 
                                 TaskStatus::CreatingPrefix  => (true, tr!("tasks-creating-prefix")),
                                 TaskStatus::InstallingDxvk  => (true, tr!("tasks-installing-dxvk")),
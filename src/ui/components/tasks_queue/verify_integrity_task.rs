@@ -131,41 +131,17 @@ impl QueuedTask for VerifyIntegrityQueuedTask {
 
                             // Otherwise verifying the file is a heavy task so we put it to the threads pool
                             tasks.push(pool.evaluate(move || -> anyhow::Result<Option<DiffFileDownload>> {
-                                // Read existing file
-                                let data = std::fs::read(&integrity_file)?;
-
                                 // Get existing file hash
-                                let hash = match info.hash {
-                                    HashType::Md5 => {
-                                        use md5::{Md5, Digest};
-
-                                        format!("{:x}", Md5::digest(&data))
-                                    },
-
-                                    HashType::Sha1 => {
-                                        use sha1::{Sha1, Digest};
-
-                                        format!("{:x}", Sha1::digest(&data))
-                                    },
-
-                                    HashType::Crc32 => {
-                                        let mut hasher = crc32fast::Hasher::new();
+                                let hash = match info.hash.hash_file(&integrity_file)? {
+                                    Some(hash) => hash,
 
-                                        hasher.update(&data);
+                                    None => match &info.hash {
+                                        HashType::Custom(name) if has_integrity_hash => {
+                                            game.driver.integrity_hash(name, std::fs::read(&integrity_file)?)?
+                                        }
 
-                                        hasher.finalize().to_string()
+                                        hash => anyhow::bail!("Failed to verify file {:?}: unsupported hash type {hash:?}", info.file.path)
                                     }
-
-                                    HashType::Xxhash32    => format!("{:x}", xxhash_rust::xxh32::xxh32(&data, 0)),
-                                    HashType::Xxhash64    => format!("{:x}", xxhash_rust::xxh64::xxh64(&data, 0)),
-                                    HashType::Xxhash3_64  => format!("{:x}", xxhash_rust::xxh3::xxh3_64(&data)),
-                                    HashType::Xxhash3_128 => format!("{:x}", xxhash_rust::xxh3::xxh3_128(&data)),
-
-                                    HashType::Custom(name) if has_integrity_hash => {
-                                        game.driver.integrity_hash(&name, data)?
-                                    }
-
-                                    _ => unimplemented!()
                                 };
 
                                 sender.send((
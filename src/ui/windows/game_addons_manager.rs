@@ -11,6 +11,7 @@ use crate::config;
 use crate::games;
 
 use crate::config::games::settings::edition_addons::GameEditionAddon;
+use crate::games::integrations::driver;
 
 use crate::games::integrations::standards::addons::{
     Addon,
@@ -140,12 +141,14 @@ impl SimpleAsyncComponent for GameAddonsManagerApp {
 
                 let mut installed_addons = HashSet::new();
 
+                let game_driver = game.driver.with_queue_timeout(driver::QUEUE_TIMEOUT);
+
                 for group in addons {
                     for addon in &group.addons {
                         let addon_path = addon.get_installation_path(&group.name, game_info.get_name(), game_info.get_edition()).unwrap();
 
                         // FIXME: handle errors
-                        if let Ok(true) = game.driver.is_addon_installed(&group.name, &addon.name, &addon_path.to_string_lossy(), game_info.get_edition()) {
+                        if let Ok(true) = game_driver.is_addon_installed(&group.name, &addon.name, &addon_path.to_string_lossy(), game_info.get_edition()) {
                             installed_addons.insert(GameEditionAddon {
                                 group: group.name.clone(),
                                 name: addon.name.clone()
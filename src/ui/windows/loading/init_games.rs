@@ -81,48 +81,67 @@ pub fn get_games_list() -> anyhow::Result<GamesList> {
 
     let games = games::list()?;
 
-    let mut installed = Vec::new();
-    let mut available = Vec::with_capacity(games.len());
-    let mut outdated = Vec::new();
+    let mut list = GamesList {
+        installed: Vec::new(),
+        available: Vec::with_capacity(games.len()),
+        outdated: Vec::new()
+    };
 
     for game in games.values() {
-        let settings = settings.get_game_settings(game)?;
-
-        let entries = get_game_entries(game, settings)?;
-
-        let installed_entries = entries.iter()
-            .filter_map(|(entry, diff)| {
-                match diff {
-                    Some(Diff { status: DiffStatus::Latest, .. }) => Some(entry),
-                    _ => None
-                }
-            })
-            .cloned();
-
-        let available_entries = entries.iter()
-            .filter_map(|(entry, diff)| {
-                diff.is_none().then_some(entry)
-            })
-            .cloned();
-
-        // TODO: handle "unavailable" status
-        let outdated_entries = entries.iter()
-            .filter_map(|(entry, diff)| {
-                match diff {
-                    Some(Diff { status: DiffStatus::Outdated, .. }) => Some(entry),
-                    _ => None
-                }
-            })
-            .cloned();
-
-        installed.extend(installed_entries);
-        available.extend(available_entries);
-        outdated.extend(outdated_entries);
+        add_game_entries(&mut list, game, settings.get_game_settings(game)?)?;
     }
 
-    Ok(GamesList {
-        installed,
-        available,
-        outdated
-    })
+    Ok(list)
+}
+
+/// Get list of the given game editions
+pub fn get_game_list(game_name: impl AsRef<str>) -> anyhow::Result<GamesList> {
+    let Some(game) = games::get(game_name.as_ref())? else {
+        anyhow::bail!("Unable to find {} integration script", game_name.as_ref());
+    };
+
+    let mut list = GamesList {
+        installed: Vec::new(),
+        available: Vec::new(),
+        outdated: Vec::new()
+    };
+
+    add_game_entries(&mut list, game, config::get().games.get_game_settings(game)?)?;
+
+    Ok(list)
+}
+
+fn add_game_entries(list: &mut GamesList, game: &Game, settings: GameSettings) -> anyhow::Result<()> {
+    let entries = get_game_entries(game, settings)?;
+
+    let installed_entries = entries.iter()
+        .filter_map(|(entry, diff)| {
+            match diff {
+                Some(Diff { status: DiffStatus::Latest, .. }) => Some(entry),
+                _ => None
+            }
+        })
+        .cloned();
+
+    let available_entries = entries.iter()
+        .filter_map(|(entry, diff)| {
+            diff.is_none().then_some(entry)
+        })
+        .cloned();
+
+    // TODO: handle "unavailable" status
+    let outdated_entries = entries.iter()
+        .filter_map(|(entry, diff)| {
+            match diff {
+                Some(Diff { status: DiffStatus::Outdated, .. }) => Some(entry),
+                _ => None
+            }
+        })
+        .cloned();
+
+    list.installed.extend(installed_entries);
+    list.available.extend(available_entries);
+    list.outdated.extend(outdated_entries);
+
+    Ok(())
 }
@@ -1,18 +1,75 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::path::Path;
 
 use serde_json::Value as Json;
 
 use anime_game_core::network::minreq;
 
 use crate::config;
-use crate::games::integrations::manifest::Manifest;
+use crate::games::integrations::manifest::{self, Manifest};
+use crate::games::integrations::signature::{self, SignatureStatus};
+use crate::games::integrations::driver;
 
 struct IntegrationInfo {
     pub source: String,
     pub manifest_body: Vec<u8>,
+    pub manifest_signature: Option<Vec<u8>>,
     pub manifest: Manifest
 }
 
+/// Try to download detached minisign signature of the file
+fn fetch_signature(uri: &str) -> anyhow::Result<Option<Vec<u8>>> {
+    let response = minreq::get(format!("{uri}.minisig"))
+        .send()?;
+
+    // HTTP OK
+    if (200..300).contains(&response.status_code) {
+        Ok(Some(response.into_bytes()))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Verify signatures of the downloaded integration files
+fn verify_files(files: &[(&str, &[u8], Option<&[u8]>)], trusted_keys: &[String], allow_unsigned: bool) -> anyhow::Result<()> {
+    for (name, data, signature) in files {
+        let status = signature::verify(data, *signature, trusted_keys)
+            .map_err(|err| anyhow::anyhow!("{name}: {err}"))?;
+
+        // Unsigned updates can't be trusted if the user configured
+        // keys since otherwise removed signatures would pass the check
+        if status == SignatureStatus::Unsigned && (!trusted_keys.is_empty() || !allow_unsigned) {
+            anyhow::bail!("{name}: integration update is not signed");
+        }
+    }
+
+    Ok(())
+}
+
+/// Save rejected integration update to the quarantine folder
+fn quarantine(path: &Path, files: &[(&str, &[u8])], reason: &str) -> anyhow::Result<()> {
+    if path.exists() {
+        std::fs::remove_dir_all(path)?;
+    }
+
+    std::fs::create_dir_all(path)?;
+
+    for (name, data) in files {
+        let file_path = path.join(name);
+
+        if let Some(parent) = file_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(file_path, data)?;
+    }
+
+    std::fs::write(path.join("reason.txt"), reason)?;
+
+    Ok(())
+}
+
 #[inline]
 pub fn update_integrations(pool: &rusty_pool::ThreadPool) -> anyhow::Result<()> {
     let config = config::get();
@@ -36,16 +93,30 @@ pub fn update_integrations(pool: &rusty_pool::ThreadPool) -> anyhow::Result<()>
 
                 for game in integrations {
                     if let Some(game) = game.as_str() {
-                        let bytes = minreq::get(format!("{source}/games/{game}/manifest.json"))
-                            .send()?.into_bytes();
-
-                        let manifest = Manifest::from_json(&serde_json::from_slice(&bytes)?)?;
-
-                        games.insert(game.to_string(), IntegrationInfo {
-                            source: format!("{source}/games/{game}"),
-                            manifest_body: bytes,
-                            manifest
-                        });
+                        let manifest_uri = format!("{source}/games/{game}/manifest.json");
+
+                        let info = (|| -> anyhow::Result<IntegrationInfo> {
+                            let bytes = minreq::get(&manifest_uri)
+                                .send()?.into_bytes();
+
+                            let manifest = Manifest::from_json(&serde_json::from_slice(&bytes)?)?;
+
+                            Ok(IntegrationInfo {
+                                source: format!("{source}/games/{game}"),
+                                manifest_body: bytes,
+                                manifest_signature: fetch_signature(&manifest_uri)?,
+                                manifest
+                            })
+                        })();
+
+                        // Don't let one broken integration block updates of the others
+                        match info {
+                            Ok(info) => {
+                                games.insert(game.to_string(), info);
+                            }
+
+                            Err(err) => tracing::error!("Failed to fetch {game} integration from {source}: {err}")
+                        }
                     }
                 }
             }
@@ -63,6 +134,7 @@ pub fn update_integrations(pool: &rusty_pool::ThreadPool) -> anyhow::Result<()>
                     games.insert(manifest.game_name.to_string(), IntegrationInfo {
                         source: source.clone(),
                         manifest_body: bytes,
+                        manifest_signature: fetch_signature(&format!("{source}/manifest.json"))?,
                         manifest
                     });
                 }
@@ -88,9 +160,19 @@ pub fn update_integrations(pool: &rusty_pool::ThreadPool) -> anyhow::Result<()>
 
     for (game, info) in games {
         let integration_path = config.games.integrations.path.join(&game);
+        let quarantine_path = config.games.integrations.path.join(".quarantine").join(&game);
 
         let manifest_path = integration_path.join("manifest.json");
-        let script_path = integration_path.join(&info.manifest.script_path);
+
+        // Keep local version of the integration which requires newer launcher
+        if !info.manifest.is_launcher_supported() {
+            tracing::warn!(
+                "Skipping {game} integration update: launcher version {} or newer is required",
+                info.manifest.launcher_min_version.as_deref().unwrap_or_default()
+            );
+
+            continue;
+        }
 
         // Spawning new threads to read a few KBs of data is more time-consuming
         // than doing it in the same thread
@@ -99,21 +181,77 @@ pub fn update_integrations(pool: &rusty_pool::ThreadPool) -> anyhow::Result<()>
             let local_manifest = serde_json::from_slice(&local_manifest)?;
             let local_manifest = Manifest::from_json(&local_manifest)?;
 
-            if local_manifest.script_version == info.manifest.script_version {
-                continue;
+            // Only accept newer versions so old signed releases
+            // can't be used to roll back the integration
+            match manifest::compare_versions(&info.manifest.script_version, &local_manifest.script_version) {
+                Ordering::Greater => (),
+
+                Ordering::Equal => continue,
+
+                Ordering::Less => {
+                    tracing::warn!(
+                        "Skipping {game} integration update: remote version {} is older than local {}",
+                        info.manifest.script_version,
+                        local_manifest.script_version
+                    );
+
+                    continue;
+                }
             }
         }
 
-        else {
-            std::fs::create_dir_all(&integration_path)?;
-        }
+        let trusted_keys = config.games.integrations.trusted_keys.clone();
+        let allow_unsigned = config.games.integrations.allow_unsigned;
 
         tasks.push(pool.evaluate(move || -> anyhow::Result<()> {
-            let script = minreq::get(format!("{}/{}", info.source, &info.manifest.script_path))
-                .send()?.into_bytes();
+            // Download script files and assets
+            let mut downloaded = Vec::new();
+
+            for file in info.manifest.files() {
+                let uri = format!("{}/{file}", info.source);
+
+                let response = minreq::get(&uri).send()?;
+
+                if !(200..300).contains(&response.status_code) {
+                    anyhow::bail!("Failed to download {game} integration file {file}: HTTP {}", response.status_code);
+                }
+
+                downloaded.push((file.to_string(), response.into_bytes(), fetch_signature(&uri)?));
+            }
+
+            let mut files = vec![("manifest.json", info.manifest_body.as_slice(), info.manifest_signature.as_deref())];
+
+            files.extend(downloaded.iter().map(|(name, data, signature)| {
+                (name.as_str(), data.as_slice(), signature.as_deref())
+            }));
+
+            // Keep local files untouched if the update can't be verified
+            if let Err(err) = verify_files(&files, &trusted_keys, allow_unsigned) {
+                tracing::error!("Rejected {game} integration update from {}: {err}", info.source);
+
+                let files = files.iter()
+                    .map(|(name, data, _)| (*name, *data))
+                    .collect::<Vec<_>>();
+
+                if let Err(err) = quarantine(&quarantine_path, &files, &err.to_string()) {
+                    tracing::error!("Failed to quarantine {game} integration update: {err}");
+                }
+
+                return Ok(());
+            }
+
+            for (name, data, _) in files {
+                let file_path = integration_path.join(name);
+
+                if let Some(parent) = file_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+
+                std::fs::write(file_path, data)?;
+            }
 
-            std::fs::write(manifest_path, info.manifest_body)?;
-            std::fs::write(script_path, script)?;
+            // Results of the previous script version are outdated
+            driver::invalidate_cache(&game);
 
             Ok(())
         }));
@@ -6,7 +6,8 @@ use crate::ui::components::game_card::CardInfo;
 
 use crate::ui::components::tasks_queue::download_diff_task::{
     DownloadDiffQueuedTask,
-    DiffOrigin
+    DiffOrigin,
+    DiffMode
 };
 
 use crate::games::integrations::Game;
@@ -96,6 +97,9 @@ pub fn get_download_addon_task(game_info: &CardInfo, addon: &Addon, group: &Addo
             group_name: group.name.clone(),
             addon_name: addon.name.clone()
         },
+        diff_mode: DiffMode::Apply {
+            version: None
+        },
         download_path
     }))
 }
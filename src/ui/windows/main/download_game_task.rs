@@ -9,7 +9,8 @@ use crate::ui::components::game_card::CardInfo;
 
 use crate::ui::components::tasks_queue::download_diff_task::{
     DownloadDiffQueuedTask,
-    DiffOrigin
+    DiffOrigin,
+    DiffMode
 };
 
 use crate::games::integrations::Game;
@@ -36,7 +37,8 @@ fn is_installed(game: &Game, game_path: &str, edition: &str) -> HeapResult<bool>
 }
 
 #[inline]
-fn get_diff(game: &Game, edition: impl AsRef<str>, game_path: impl AsRef<str>) -> HeapResult<DiffInfo> {
+/// Get update of the installed game with its target version
+fn get_diff(game: &Game, edition: impl AsRef<str>, game_path: impl AsRef<str>) -> HeapResult<(String, DiffInfo)> {
     game.driver.get_game_diff(game_path.as_ref(), edition.as_ref())
         .map_err(|err| MainAppMsg::ShowToast {
             title: tr!("game-find-diff-failed", {
@@ -44,7 +46,7 @@ fn get_diff(game: &Game, edition: impl AsRef<str>, game_path: impl AsRef<str>) -
             }),
             message: Some(err.to_string())
         })?
-        .and_then(|diff| diff.diff)
+        .and_then(|diff| Some((diff.latest_version, diff.diff?)))
         .ok_or_else(|| Box::new(MainAppMsg::ShowToast {
             title: tr!("game-not-installed", {
                 "game-title" = game.manifest.game_title.clone()
@@ -66,10 +68,15 @@ fn get_download(game: &Game, edition: &str) -> HeapResult<DiffInfo> {
 }
 
 #[inline]
-fn get_diff_or_download(game: &Game, game_path: &str, edition: &str) -> HeapResult<DiffInfo> {
-    is_installed(game, game_path, edition)?
-        .then(|| get_diff(game, edition, game_path))
-        .unwrap_or_else(|| get_download(game, edition))
+/// Get game update or installation files
+/// 
+/// Version is only returned for updates
+fn get_diff_or_download(game: &Game, game_path: &str, edition: &str) -> HeapResult<(Option<String>, DiffInfo)> {
+    if is_installed(game, game_path, edition)? {
+        get_diff(game, edition, game_path).map(|(version, diff)| (Some(version), diff))
+    } else {
+        get_download(game, edition).map(|download| (None, download))
+    }
 }
 
 #[inline]
@@ -113,18 +120,62 @@ pub fn get_download_game_task(game_info: &CardInfo, config: &config::Config) ->
     // Enabled game addons
     let enabled_addons = &settings.addons[game_info.get_edition()];
 
+    let (version, diff_info) = get_diff_or_download(
+        game,
+        &game_path.to_string_lossy(),
+        game_info.get_edition()
+    )?;
+
     Ok(DownloadGameResult {
         game_task: Box::new(DownloadDiffQueuedTask {
             card_info: game_info.clone(),
             download_path: game_path.clone(),
-            diff_info: get_diff_or_download(
-                game,
-                &game_path.to_string_lossy(),
-                game_info.get_edition()
-            )?,
-            diff_origin: DiffOrigin::Game
+            diff_info,
+            diff_origin: DiffOrigin::Game,
+            diff_mode: DiffMode::Apply {
+                version
+            }
         }),
 
         download_addons: get_addons(game, game_info, game_info.get_edition(), enabled_addons)?
     })
 }
+
+#[inline]
+pub fn get_predownload_game_task(game_info: &CardInfo, config: &config::Config) -> HeapResult<Box<DownloadDiffQueuedTask>> {
+    let game = unsafe {
+        games::get_unsafe(game_info.get_name())
+    };
+
+    let settings = get_settings(game, config)?;
+
+    // Game installation path
+    let game_path = &settings.paths[game_info.get_edition()].game;
+
+    let predownload = game.driver.get_game_predownload(&game_path.to_string_lossy(), game_info.get_edition())
+        .map_err(|err| Box::new(MainAppMsg::ShowToast {
+            title: tr!("game-find-predownload-failed", {
+                "game-title" = game.manifest.game_title.clone()
+            }),
+            message: Some(err.to_string())
+        }))?;
+
+    let Some((version, diff_info)) = predownload.and_then(|diff| Some((diff.latest_version, diff.diff?))) else {
+        return Err(Box::new(MainAppMsg::ShowToast {
+            title: tr!("game-predownload-unavailable", {
+                "game-title" = game.manifest.game_title.clone()
+            }),
+            message: None
+        }));
+    };
+
+    Ok(Box::new(DownloadDiffQueuedTask {
+        card_info: game_info.clone(),
+        download_path: game_path.clone(),
+        diff_info,
+        diff_origin: DiffOrigin::Game,
+        diff_mode: DiffMode::Predownload {
+            version
+        }
+    }))
+}
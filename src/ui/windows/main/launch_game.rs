@@ -1,4 +1,5 @@
 use std::process::Command;
+use std::os::unix::process::CommandExt;
 use std::path::PathBuf;
 
 use adw::prelude::*;
@@ -21,6 +22,7 @@ use crate::games::metadata::{
 };
 
 use crate::games::integrations::Game;
+use crate::games::integrations::builtins::process;
 
 use crate::games::integrations::standards::diff::{
     Diff,
@@ -199,6 +201,39 @@ pub fn prepare_launch_args(config: &config::Config) -> String {
     launch_args
 }
 
+/// Run post-launch hook of the integration script when dropped,
+/// so it cleans up after the game even if launching failed
+struct PostLaunchGuard<'a> {
+    game: &'a Game,
+    game_path: String,
+    edition: String,
+
+    /// Game starting timestamp, if it was started
+    started_at: Option<i64>,
+    exit_code: Option<i32>
+}
+
+impl Drop for PostLaunchGuard<'_> {
+    fn drop(&mut self) {
+        let playtime = self.started_at
+            .map(|started_at| chrono::Utc::now().timestamp() - started_at)
+            .and_then(|playtime| u64::try_from(playtime).ok())
+            .unwrap_or_default();
+
+        let result = self.game.driver.has_game_post_launch().and_then(|has_post_launch| {
+            if has_post_launch {
+                self.game.driver.run_game_post_launch(&self.game_path, &self.edition, self.exit_code, playtime)?;
+            }
+
+            Ok(())
+        });
+
+        if let Err(err) = result {
+            tracing::error!("Failed to run post-launch hook of {}: {err}", self.game.manifest.game_title);
+        }
+    }
+}
+
 #[inline]
 #[tracing::instrument]
 pub fn launch_game(info: &CardInfo) -> anyhow::Result<()> {
@@ -233,6 +268,20 @@ pub fn launch_game(info: &CardInfo) -> anyhow::Result<()> {
         info.get_edition()
     )?;
 
+    // Let the integration script prepare the game
+    if game.driver.has_game_pre_launch()? {
+        game.driver.run_game_pre_launch(&game_path.to_string_lossy(), info.get_edition())?;
+    }
+
+    // Let the integration script clean up after the game
+    let mut post_launch = PostLaunchGuard {
+        game,
+        game_path: game_path.to_string_lossy().to_string(),
+        edition: info.get_edition().to_string(),
+        started_at: None,
+        exit_code: None
+    };
+
     // Get selected wine version
     let wine = Wine::from_config()?;
 
@@ -271,16 +320,28 @@ pub fn launch_game(info: &CardInfo) -> anyhow::Result<()> {
     // Get game starting timestamp
     let started_at = chrono::Utc::now().timestamp();
 
-    // Run the game
-    command.current_dir(&game_path)
-        .spawn()?
-        .wait()?;
+    post_launch.started_at = Some(started_at);
+
+    // Run the game in its own process group so its processes
+    // can be found after the launching command is finished
+    let mut child = command.current_dir(&game_path)
+        .process_group(0)
+        .spawn()?;
+
+    // Let integration script find processes of the game
+    let launched = process::track_launched(info.get_name(), info.get_edition(), child.id());
+
+    let status = child.wait()?;
+
+    post_launch.exit_code = status.code();
 
     // Wait while the game is running
     while game.driver.is_process_running(&game_path.to_string_lossy(), info.get_edition())? {
         std::thread::sleep(std::time::Duration::from_secs(5));
     }
 
+    drop(launched);
+
     // Get game stopping timestamp
     let stopped_at = chrono::Utc::now().timestamp();
 
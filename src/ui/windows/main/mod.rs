@@ -22,6 +22,7 @@ use crate::components::dxvk::*;
 use crate::config::games::settings::edition_addons::GameEditionAddon;
 
 use crate::games::metadata::LauncherMetadata;
+use crate::games::integrations::driver;
 use crate::games::integrations::standards::addons::{
     Addon,
     AddonsGroup
@@ -35,6 +36,7 @@ use crate::ui::windows::about::{
 };
 
 use crate::ui::windows::loading::load_app::LoadingResult;
+use crate::ui::windows::loading::init_games::{self, GamesList};
 
 use crate::ui::windows::game_addons_manager::{
     GameAddonsManagerApp,
@@ -108,6 +110,14 @@ pub struct MainApp {
 #[derive(Debug)]
 pub enum MainAppMsg {
     InitMainApp(LoadingResult),
+    RefreshLibrary,
+    SetLibrary(GamesList),
+    ReloadGame(String),
+
+    SetReloadedGame {
+        game_name: String,
+        games_list: GamesList
+    },
 
     OpenDetails {
         info: CardInfo,
@@ -130,6 +140,7 @@ pub enum MainAppMsg {
     ToggleTasksFlap,
 
     AddDownloadGameTask(CardInfo),
+    AddPredownloadGameTask(CardInfo),
     AddVerifyGameTask(CardInfo),
     FinishQueuedTask(CardInfo),
 
@@ -169,6 +180,7 @@ relm4::new_action_group!(WindowActionGroup, "win");
 relm4::new_stateless_action!(LauncherFolder, WindowActionGroup, "launcher_folder");
 relm4::new_stateless_action!(ConfigFile, WindowActionGroup, "config_file");
 relm4::new_stateless_action!(DebugFile, WindowActionGroup, "debug_file");
+relm4::new_stateless_action!(RefreshLibrary, WindowActionGroup, "refresh_library");
 
 relm4::new_stateless_action!(About, WindowActionGroup, "about");
 
@@ -186,6 +198,10 @@ impl SimpleAsyncComponent for MainApp {
                 &tr!("main-menu-debug-file")      => DebugFile,
             },
 
+            section! {
+                &tr!("main-menu-refresh-library") => RefreshLibrary
+            },
+
             section! {
                 &tr!("main-menu-about") => About
             }
@@ -426,6 +442,9 @@ impl SimpleAsyncComponent for MainApp {
                     GameDetailsComponentOutput::DownloadGame(info)
                         => MainAppMsg::AddDownloadGameTask(info),
 
+                    GameDetailsComponentOutput::PredownloadGame(info)
+                        => MainAppMsg::AddPredownloadGameTask(info),
+
                     GameDetailsComponentOutput::VerifyGame(info)
                         => MainAppMsg::AddVerifyGameTask(info),
 
@@ -578,65 +597,36 @@ impl SimpleAsyncComponent for MainApp {
             }
         })));
 
+        group.add_action::<RefreshLibrary>(RelmAction::new_stateless(gtk::glib::clone!(@strong sender => move |_| {
+            sender.input(MainAppMsg::RefreshLibrary);
+        })));
+
         group.add_action::<About>(RelmAction::new_stateless(move |_| {
             about_dialog_broker.send(AboutDialogMsg::Show);
         }));
 
         widgets.window.insert_action_group("win", Some(&group.into_action_group()));
 
+        // Reload changed integration scripts without restarting the launcher
+        if *crate::APP_DEBUG {
+            let sender = sender.clone();
+
+            let result = games::hot_reload::watch(move |game_name| {
+                sender.input(MainAppMsg::ReloadGame(game_name));
+            });
+
+            if let Err(err) = result {
+                tracing::error!("Failed to watch integration scripts: {err}");
+            }
+        }
+
         AsyncComponentParts { model, widgets }
     }
 
     async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
         match msg {
             MainAppMsg::InitMainApp(init) => {
-                for game in init.games_list.available {
-                    let card = CardInfo::Game {
-                        name: game.game_name.clone(),
-                        title: game.game_title.clone(),
-                        developer: game.game_developer.clone(),
-                        edition: game.edition.name.clone(),
-                        picture_uri: game.card_picture.clone()
-                    };
-
-                    self.available_games_indexes.insert(
-                        card.to_owned(),
-                        self.available_games.guard().push_back(card.to_owned())
-                    );
-                }
-
-                for game in init.games_list.installed {
-                    let card = CardInfo::Game {
-                        name: game.game_name.clone(),
-                        title: game.game_title.clone(),
-                        developer: game.game_developer.clone(),
-                        edition: game.edition.name.clone(),
-                        picture_uri: game.card_picture.clone()
-                    };
-
-                    self.installed_games_indexes.insert(
-                        card.to_owned(),
-                        self.installed_games.guard().push_back(card.to_owned())
-                    );
-                }
-
-                for game in init.games_list.outdated {
-                    let card = CardInfo::Game {
-                        name: game.game_name.clone(),
-                        title: game.game_title.clone(),
-                        developer: game.game_developer.clone(),
-                        edition: game.edition.name.clone(),
-                        picture_uri: game.card_picture.clone()
-                    };
-
-                    self.outdated_games_indexes.insert(
-                        card.to_owned(),
-                        self.outdated_games.guard().push_back(card.to_owned())
-                    );
-                }
-
-                self.available_games.broadcast(CardComponentInput::SetInstalled(false));
-                self.outdated_games.broadcast(CardComponentInput::SetInstalled(false));
+                self.set_games_list(init.games_list);
 
                 if let Some(wine) = init.download_wine {
                     sender.input(MainAppMsg::AddDownloadWineTask(wine));
@@ -673,6 +663,117 @@ impl SimpleAsyncComponent for MainApp {
                 }
             }
 
+            MainAppMsg::RefreshLibrary => {
+                // Drop cached integration results to request new editions and addons
+                driver::invalidate_all_caches();
+
+                // Integration scripts can make network requests
+                // so the games list is requested in background
+                std::thread::spawn(move || {
+                    let games_list = init_games::get_games_list().and_then(|games_list| {
+                        init_games::register_games_styles()?;
+
+                        Ok(games_list)
+                    });
+
+                    match games_list {
+                        Ok(games_list) => sender.input(MainAppMsg::SetLibrary(games_list)),
+
+                        Err(err) => {
+                            tracing::error!("Failed to refresh games library: {err}");
+
+                            sender.input(MainAppMsg::ShowToast {
+                                title: tr!("main-refresh-library-failed"),
+                                message: Some(err.to_string())
+                            });
+                        }
+                    }
+                });
+            }
+
+            MainAppMsg::SetLibrary(games_list) => {
+                self.installed_games.guard().clear();
+                self.outdated_games.guard().clear();
+                self.available_games.guard().clear();
+
+                self.installed_games_indexes.clear();
+                self.outdated_games_indexes.clear();
+                self.available_games_indexes.clear();
+
+                self.set_games_list(games_list);
+            }
+
+            MainAppMsg::ReloadGame(game_name) => {
+                // Reloaded script can make network requests
+                // so the game cards are requested in background
+                std::thread::spawn(move || {
+                    let games_list = games::hot_reload::reload(&game_name)
+                        .and_then(|_| init_games::get_game_list(&game_name))
+                        .and_then(|games_list| {
+                            init_games::register_games_styles()?;
+
+                            Ok(games_list)
+                        });
+
+                    match games_list {
+                        Ok(games_list) => sender.input(MainAppMsg::SetReloadedGame {
+                            game_name,
+                            games_list
+                        }),
+
+                        Err(err) => {
+                            tracing::error!("Failed to reload {game_name} integration: {err}");
+
+                            sender.input(MainAppMsg::ShowToast {
+                                title: tr!("main-reload-integration-failed", {
+                                    "game-name" = game_name.as_str()
+                                }),
+                                message: Some(err.to_string())
+                            });
+                        }
+                    }
+                });
+            }
+
+            MainAppMsg::SetReloadedGame { game_name, games_list } => {
+                // Replace cards of the reloaded game only
+                let lists = [
+                    (&mut self.installed_games, &mut self.installed_games_indexes),
+                    (&mut self.outdated_games, &mut self.outdated_games_indexes),
+                    (&mut self.available_games, &mut self.available_games_indexes)
+                ];
+
+                for (cards, indexes) in lists {
+                    let game_cards = indexes.keys()
+                        .filter(|info| info.get_name() == game_name)
+                        .cloned()
+                        .collect::<Vec<_>>();
+
+                    for info in game_cards {
+                        if let Some(index) = indexes.remove(&info) {
+                            cards.guard().remove(index.current_index());
+                        }
+                    }
+                }
+
+                self.set_games_list(games_list);
+
+                // Update opened details page
+                if self.game_details_info.get_name() == game_name {
+                    let info = self.installed_games_indexes.keys()
+                        .chain(self.outdated_games_indexes.keys())
+                        .chain(self.available_games_indexes.keys())
+                        .find(|info| info.get_name() == game_name && info.get_edition() == self.game_details_info.get_edition())
+                        .cloned();
+
+                    if let Some(info) = info {
+                        self.game_details_info = info.clone();
+
+                        self.game_details.emit(GameDetailsComponentInput::SetInfo(info));
+                    }
+                }
+            }
+
             MainAppMsg::OpenDetails { info, installed, running } => {
                 self.game_details_info = info.clone();
 
@@ -682,6 +783,7 @@ impl SimpleAsyncComponent for MainApp {
 
                 if !installed {
                     self.game_details.emit(GameDetailsComponentInput::SetStatus(None));
+                    self.game_details.emit(GameDetailsComponentInput::SetPredownload(None));
                 }
 
                 else {
@@ -700,7 +802,9 @@ impl SimpleAsyncComponent for MainApp {
 
                     self.game_details.emit(GameDetailsComponentInput::SetMetadata(metadata));
 
-                    match game.driver.get_game_status(&paths.game.to_string_lossy(), info.get_edition()) {
+                    let game_driver = game.driver.with_queue_timeout(driver::QUEUE_TIMEOUT);
+
+                    match game_driver.get_game_status(&paths.game.to_string_lossy(), info.get_edition()) {
                         Ok(status) => {
                             self.game_details.emit(GameDetailsComponentInput::SetStatus(status));
                         }
@@ -714,6 +818,26 @@ impl SimpleAsyncComponent for MainApp {
                             });
                         }
                     }
+
+                    match game_driver.get_game_predownload(&paths.game.to_string_lossy(), info.get_edition()) {
+                        Ok(diff) => {
+                            let version = diff.filter(|diff| diff.diff.is_some())
+                                .map(|diff| diff.latest_version);
+
+                            self.game_details.emit(GameDetailsComponentInput::SetPredownload(version));
+                        }
+
+                        Err(err) => {
+                            self.game_details.emit(GameDetailsComponentInput::SetPredownload(None));
+
+                            sender.input(MainAppMsg::ShowToast {
+                                title: tr!("game-find-predownload-failed", {
+                                    "game-title" = info.get_title()
+                                }),
+                                message: Some(err.to_string())
+                            });
+                        }
+                    }
                 }
 
                 self.leaflet.navigate(adw::NavigationDirection::Forward);
@@ -736,7 +860,7 @@ impl SimpleAsyncComponent for MainApp {
 
                 let game = games::get_unsafe(game_info.get_name());
 
-                match game.driver.get_addons_list(game_info.get_edition()) {
+                match game.driver.with_queue_timeout(driver::QUEUE_TIMEOUT).get_addons_list(game_info.get_edition()) {
                     Ok(addons) => {
                         controller.emit(GameAddonsManagerAppMsg::SetGameInfo {
                             game_info,
@@ -821,6 +945,17 @@ impl SimpleAsyncComponent for MainApp {
                 }
             }
 
+            // Pre-download doesn't change the installed game
+            // so its card stays in the installed games list
+            MainAppMsg::AddPredownloadGameTask(game_info) => {
+                let config = config::get();
+
+                match download_game_task::get_predownload_game_task(&game_info, &config) {
+                    Ok(task) => self.tasks_queue.emit(TasksQueueComponentInput::AddTask(task)),
+                    Err(err) => sender.input(*err)
+                }
+            }
+
             MainAppMsg::AddVerifyGameTask(game_info) => {
                 let config = config::get();
 
@@ -1016,13 +1151,49 @@ impl SimpleAsyncComponent for MainApp {
                 if let Some(message) = message {
                     toast.set_button_label(Some(&tr!("dialog-toast-details")));
 
+                    // Multiline errors (e.g. from the integration scripts) contain
+                    // short description in the first line and details in the rest
+                    let (summary, details) = match message.split_once('\n') {
+                        Some((summary, details)) => (summary, Some(details.trim())),
+                        None => (message.as_str(), None)
+                    };
+
                     let dialog = adw::MessageDialog::new(
                         Some(window),
                         Some(&title),
-                        Some(&message)
+                        Some(summary)
                     );
 
                     dialog.add_response("close", &tr!("dialog-close"));
+
+                    if let Some(details) = details {
+                        let label = gtk::Label::new(Some(details));
+
+                        label.set_selectable(true);
+                        label.set_wrap(true);
+                        label.set_xalign(0.0);
+                        label.add_css_class("monospace");
+
+                        let scrolled_window = gtk::ScrolledWindow::new();
+
+                        scrolled_window.set_child(Some(&label));
+                        scrolled_window.set_min_content_height(200);
+                        scrolled_window.set_max_content_height(400);
+                        scrolled_window.set_propagate_natural_height(true);
+
+                        let expander = gtk::Expander::new(Some(&tr!("dialog-error-details")));
+
+                        expander.set_child(Some(&scrolled_window));
+
+                        dialog.set_extra_child(Some(&expander));
+
+                        dialog.add_response("copy", &tr!("dialog-copy-details"));
+
+                        dialog.connect_response(Some("copy"), move |dialog, _| {
+                            dialog.clipboard().set_text(&message);
+                        });
+                    }
+
                     dialog.add_response("save", &tr!("dialog-save"));
 
                     dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
@@ -1043,3 +1214,40 @@ impl SimpleAsyncComponent for MainApp {
         }
     }
 }
+
+impl MainApp {
+    /// Add game cards from the given list
+    /// 
+    /// Running and queued games keep their current cards
+    fn set_games_list(&mut self, games_list: GamesList) {
+        let lists = [
+            (games_list.available, &mut self.available_games, &mut self.available_games_indexes),
+            (games_list.installed, &mut self.installed_games, &mut self.installed_games_indexes),
+            (games_list.outdated, &mut self.outdated_games, &mut self.outdated_games_indexes)
+        ];
+
+        for (entries, cards, indexes) in lists {
+            for game in entries {
+                let card = CardInfo::Game {
+                    name: game.game_name.clone(),
+                    title: game.game_title.clone(),
+                    developer: game.game_developer.clone(),
+                    edition: game.edition.name.clone(),
+                    picture_uri: game.card_picture.clone()
+                };
+
+                if self.running_games_indexes.contains_key(&card) || self.queued_games_indexes.contains_key(&card) {
+                    continue;
+                }
+
+                indexes.insert(
+                    card.to_owned(),
+                    cards.guard().push_back(card.to_owned())
+                );
+            }
+        }
+
+        self.available_games.broadcast(CardComponentInput::SetInstalled(false));
+        self.outdated_games.broadcast(CardComponentInput::SetInstalled(false));
+    }
+}